@@ -0,0 +1,20 @@
+use crate::core::game::GamePhase;
+use crate::systems::character::ATTRIBUTES;
+
+// State of the character sheet: the highlighted attribute and the screen to
+// go back to when it closes.
+#[derive(PartialEq)]
+pub struct CharacterSheet {
+    pub selected: usize,
+    pub previous: Box<GamePhase>,
+}
+
+impl CharacterSheet {
+    pub fn new(previous: GamePhase) -> Self {
+        Self { selected: 0, previous: Box::new(previous) }
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        self.selected = (self.selected as i32 + delta).rem_euclid(ATTRIBUTES.len() as i32) as usize;
+    }
+}
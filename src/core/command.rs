@@ -0,0 +1,153 @@
+// What the player asked for, independent of which key they pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    Move(Direction),
+    // Go into the settlement or building underfoot
+    Enter,
+    // Leave the current location
+    Exit,
+    // Quit the game
+    Quit,
+    // Back out of the current screen or prompt
+    Cancel,
+    Confirm,
+    OpenMap,
+    Journal,
+    Survey,
+    TravelToSettlement,
+    Explore,
+    StairsUp,
+    StairsDown,
+    Inventory,
+    // Open the list of class abilities
+    Abilities,
+    // Open the character sheet to check progress and spend attribute points
+    CharacterSheet,
+    // Open the list of how each faction regards the player
+    Reputation,
+    // Do something the place allows, such as praying at a shrine
+    Interact(Interaction),
+    Camp,
+    Save,
+    Load,
+    ZoomIn,
+    ZoomOut,
+    CentreMap,
+    ToggleRelief,
+    AddNote,
+    DeleteNote,
+    NextTab,
+    CycleSort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+// Things the player can do depending on where they stand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interaction {
+    Pray,
+    Cleanse,
+    Search,
+    Rest,
+    // Look at what a feature close by has to offer
+    Visit,
+    // Buy and sell at a market or trading post
+    Trade,
+}
+
+impl Interaction {
+    pub fn label(&self) -> &str {
+        match self {
+            Interaction::Pray => "Pray",
+            Interaction::Cleanse => "Cleanse",
+            Interaction::Search => "Search",
+            Interaction::Rest => "Rest",
+            Interaction::Visit => "Visit",
+            Interaction::Trade => "Trade",
+        }
+    }
+}
+
+impl Direction {
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+            Direction::NorthEast => (1, -1),
+            Direction::NorthWest => (-1, -1),
+            Direction::SouthEast => (1, 1),
+            Direction::SouthWest => (-1, 1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.delta();
+        dx != 0 && dy != 0
+    }
+}
+
+// Names used for commands in the key binding config file
+const COMMAND_NAMES: &[(&str, Command)] = &[
+    ("move_north", Command::Move(Direction::North)),
+    ("move_south", Command::Move(Direction::South)),
+    ("move_east", Command::Move(Direction::East)),
+    ("move_west", Command::Move(Direction::West)),
+    ("move_north_east", Command::Move(Direction::NorthEast)),
+    ("move_north_west", Command::Move(Direction::NorthWest)),
+    ("move_south_east", Command::Move(Direction::SouthEast)),
+    ("move_south_west", Command::Move(Direction::SouthWest)),
+    ("enter", Command::Enter),
+    ("exit", Command::Exit),
+    ("quit", Command::Quit),
+    ("cancel", Command::Cancel),
+    ("confirm", Command::Confirm),
+    ("open_map", Command::OpenMap),
+    ("journal", Command::Journal),
+    ("survey", Command::Survey),
+    ("travel", Command::TravelToSettlement),
+    ("explore", Command::Explore),
+    ("stairs_up", Command::StairsUp),
+    ("stairs_down", Command::StairsDown),
+    ("inventory", Command::Inventory),
+    ("abilities", Command::Abilities),
+    ("character", Command::CharacterSheet),
+    ("reputation", Command::Reputation),
+    ("pray", Command::Interact(Interaction::Pray)),
+    ("cleanse", Command::Interact(Interaction::Cleanse)),
+    ("search", Command::Interact(Interaction::Search)),
+    ("rest", Command::Interact(Interaction::Rest)),
+    ("visit", Command::Interact(Interaction::Visit)),
+    ("trade", Command::Interact(Interaction::Trade)),
+    ("camp", Command::Camp),
+    ("save", Command::Save),
+    ("load", Command::Load),
+    ("zoom_in", Command::ZoomIn),
+    ("zoom_out", Command::ZoomOut),
+    ("centre_map", Command::CentreMap),
+    ("toggle_relief", Command::ToggleRelief),
+    ("add_note", Command::AddNote),
+    ("delete_note", Command::DeleteNote),
+    ("next_tab", Command::NextTab),
+    ("cycle_sort", Command::CycleSort),
+];
+
+impl Command {
+    pub fn from_name(name: &str) -> Option<Command> {
+        COMMAND_NAMES
+            .iter()
+            .find(|(command_name, _)| *command_name == name)
+            .map(|(_, command)| *command)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::core::game::GamePhase;
+use crate::generators::location_generator::Feature;
+
+// State of the screen listing what a feature offers: which feature, the
+// highlighted offer and the screen to go back to when it closes.
+#[derive(PartialEq)]
+pub struct FeatureView {
+    pub feature: Feature,
+    pub selected: usize,
+    pub previous: Box<GamePhase>,
+}
+
+impl FeatureView {
+    pub fn new(feature: Feature, previous: GamePhase) -> Self {
+        Self { feature, selected: 0, previous: Box::new(previous) }
+    }
+
+    // Move the highlight over a list of `len` offers
+    pub fn move_selection(&mut self, delta: i32, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected as i32 + delta).rem_euclid(len as i32) as usize;
+        }
+    }
+}
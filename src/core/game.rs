@@ -1,15 +1,191 @@
 use crate::systems::world::World;
 use crate::systems::player::Player;
+use crate::systems::character::{Attribute, Character};
+use crate::systems::position::Position;
 use crate::systems::location::{Location, LocationState, Species};
-use crate::generators::location_generator::{LocationGenerator, LocationMap};
+use crate::systems::item::Item;
+use crate::systems::species::Attitude;
+use crate::systems::world::TerrainType;
+use crate::generators::location_generator::{Feature, FeatureType, LocationGenerator, LocationMap, LocationTileType, NpcRole, location_seed};
+use crate::generators::interior_generator::{BuildingKind, InteriorGenerator};
+use crate::core::map_view::MapView;
+use crate::core::journal_view::JournalView;
+use crate::core::character_sheet::CharacterSheet;
+use crate::core::inventory_view::InventoryView;
+use crate::core::feature_view::FeatureView;
+use crate::core::trade_view::{TradeSide, TradeView};
+use crate::core::reputation_view::ReputationView;
+use crate::core::menu::MenuView;
+use crate::core::keymap::{KeyContext, Keymap};
+use crate::core::command::{Command, Interaction};
+use crate::prelude::Result;
+use crate::systems::journal::Journal;
+use crate::systems::clock::{GameClock, world_step_time};
+use crate::systems::pathfinding::{find_nearest, find_path};
+use crate::systems::class::AbilityEffect;
+use crate::systems::status::{BLESSED, CURSED, EXHAUSTED};
+use crate::systems::services::{Deal, Offer, Service, TRAINING_CAP, feature_offers, feature_services, market_wares};
+use crate::systems::economy::Market;
+use crate::systems::settlement::simulate_day;
+use crate::systems::reputation::{Faction, disposition};
+use crate::systems::combat::{Combat, CombatAction, Enemy, hit_chance, monsters_in, roll_attack};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::collections::{HashSet, VecDeque};
 
 #[derive(PartialEq)]
 pub enum GamePhase {
-    Menu,
+    Menu(MenuView),
     PlayingWorld,
-    PlayingLocation(LocationMap),
-    Map,
-    GameOver,
+    PlayingLocation(LocationStack),
+    Map(MapView),
+    Journal(JournalView),
+    Combat(Combat),
+    CharacterSheet(CharacterSheet),
+    Inventory(InventoryView),
+    // What a feature in a settlement offers
+    Feature(FeatureView),
+    // Buying and selling at a market
+    Trade(TradeView),
+    // How each faction regards the player
+    Reputation(ReputationView),
+    GameOver(GameSummary),
+    // The program should close
+    Exit,
+}
+
+// How a run went, shown on the game-over screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSummary {
+    pub name: String,
+    pub days: u64,
+    pub places: usize,
+    pub xp: i32,
+    pub level: u32,
+    pub cause: String,
+}
+
+// One level of nesting: the floors of the place, which one the player is on,
+// and the tile in the parent map the player returns to when leaving it.
+#[derive(PartialEq)]
+pub struct LocationFrame {
+    pub floors: Vec<LocationMap>,
+    pub floor: usize,
+    pub return_pos: Position,
+}
+
+impl LocationFrame {
+    fn new(floors: Vec<LocationMap>, return_pos: Position) -> Self {
+        // Start on ground level, wherever it sits in the list of floors
+        let floor = floors.iter().position(|map| map.level == 0).unwrap_or(0);
+        Self { floors, floor, return_pos }
+    }
+}
+
+// Nested locations, outermost settlement first and the building the player
+// is currently inside last.
+#[derive(PartialEq)]
+pub struct LocationStack {
+    frames: Vec<LocationFrame>,
+}
+
+impl LocationStack {
+    pub fn new(floors: Vec<LocationMap>) -> Self {
+        Self {
+            frames: vec![LocationFrame::new(floors, Position::new(0, 0))],
+        }
+    }
+
+    fn top(&self) -> &LocationFrame {
+        &self.frames[self.frames.len() - 1]
+    }
+
+    pub fn current(&self) -> &LocationMap {
+        let frame = self.top();
+        &frame.floors[frame.floor]
+    }
+
+    pub fn current_mut(&mut self) -> &mut LocationMap {
+        let frame = self.frames.last_mut().expect("location stack is never empty");
+        &mut frame.floors[frame.floor]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn push(&mut self, floors: Vec<LocationMap>, return_pos: Position) {
+        self.frames.push(LocationFrame::new(floors, return_pos));
+    }
+
+    // Leave the innermost location. Returns the tile to stand on in the
+    // parent map, or None when leaving the outermost one.
+    pub fn pop(&mut self) -> Option<Position> {
+        if self.frames.len() > 1 {
+            self.frames.pop().map(|frame| frame.return_pos)
+        } else {
+            None
+        }
+    }
+
+    // Move to the floor `delta` levels up (or down when negative). Returns
+    // false when there is no such floor.
+    pub fn change_floor(&mut self, delta: i32) -> bool {
+        let frame = self.frames.last_mut().expect("location stack is never empty");
+        let target = frame.floors[frame.floor].level + delta;
+        match frame.floors.iter().position(|map| map.level == target) {
+            Some(index) => {
+                frame.floor = index;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// How long a night in camp lasts
+const CAMP_HOURS: u64 = 8;
+// Tiles of sight lost in the dark
+const NIGHT_SIGHT_PENALTY: i32 = 2;
+// Percent chance of running into a monster with each step through the wilds
+const ENCOUNTER_CHANCE: u32 = 3;
+// Hours on the move before the player is exhausted
+const EXHAUSTION_HOURS: u64 = 18;
+// Game time in one turn of a status effect outside of combat
+const EFFECT_TURN_SECONDS: u64 = 60 * 60;
+// Condition worn gear loses in every fight won
+const WEAR_PER_FIGHT: u32 = 5;
+// XP for each settlement added to the journal
+const DISCOVERY_XP: i32 = 10;
+// Hours spent on each interaction
+const PRAYER_HOURS: u64 = 1;
+const CLEANSE_HOURS: u64 = 2;
+const SEARCH_HOURS: u64 = 2;
+// XP for lifting the curse on a place
+const CLEANSE_XP: i32 = 25;
+// Extra percent chance to cleanse while blessed
+const BLESSED_CLEANSE_BONUS: i32 = 25;
+// Base percent chance that searching turns something up, plus 5 per point of luck
+const SEARCH_CHANCE: i32 = 30;
+// What gets left behind in empty places
+const SALVAGE: [&str; 5] = ["Rations", "Lockpicks", "Dagger", "Leather boots", "Silver ring"];
+// Reputation gained with a settlement's faction for business done there,
+// for lifting the curse on one of their places, and for a monster slain
+// within DEFENCE_RANGE tiles of one of their towns
+const CUSTOM_STANDING: i32 = 1;
+const CLEANSE_STANDING: i32 = 15;
+const DEFENCE_STANDING: i32 = 3;
+const DEFENCE_RANGE: u32 = 10;
+// Reputation lost for picking through a hidden place its people showed you
+const LOOTING_STANDING: i32 = -20;
+
+// Called with every newly generated world, e.g. to dump it for debugging
+pub type WorldHook = Box<dyn Fn(&World) -> Result<()>>;
+
+// A journey the player walks one step at a time until arriving or being interrupted
+pub struct AutoTravel {
+    pub destination: Position,
+    pub path: VecDeque<Position>,
 }
 
 pub struct Game {
@@ -18,31 +194,781 @@ pub struct Game {
     pub view_radius: i32,
     pub phase: GamePhase,
     pub current_message: Option<String>,
+    // Shade the full map by elevation
+    pub map_relief: bool,
+    pub auto_travel: Option<AutoTravel>,
+    // Places discovered and markers placed by the player
+    pub journal: Journal,
+    pub keymap: Keymap,
+    pub clock: GameClock,
+    pub rng: StdRng,
+    // Percent chance of an encounter per step off the roads
+    pub encounter_chance: u32,
+    // Steps left before monsters can notice the player again
+    pub unseen_steps: u32,
+    // Highlighted ability while the ability list is open
+    pub ability_menu: Option<usize>,
+    // When the player last woke from camping, in clock seconds
+    pub awake_since: u64,
+    // The day the world's settlements have been simulated up to
+    pub simulated_day: u64,
+    // The clock seconds status effects have run up to
+    pub effects_time: u64,
+    pub on_new_world: Option<WorldHook>,
 }
 
 impl Game {
+    pub fn new(player: Player, world: World) -> Self {
+        let rng = StdRng::seed_from_u64(world.seed);
+        Self {
+            player,
+            world,
+            view_radius: 10,
+            phase: GamePhase::PlayingWorld,
+            current_message: None,
+            map_relief: false,
+            auto_travel: None,
+            journal: Journal::default(),
+            keymap: Keymap::default(),
+            clock: GameClock::default(),
+            rng,
+            encounter_chance: ENCOUNTER_CHANCE,
+            unseen_steps: 0,
+            ability_menu: None,
+            awake_since: GameClock::default().seconds,
+            simulated_day: GameClock::default().day(),
+            effects_time: GameClock::default().seconds,
+            on_new_world: None,
+        }
+    }
+
+    // Generate a world from `seed` and start the character off in the
+    // settlement of their species nearest the middle of the map
+    pub fn start(seed: u32, width: usize, height: usize, character: Character) -> Self {
+        let world = World::new(seed, width, height);
+        let centre = Position::new(width / 2, height / 2);
+        let start = world.find_nearest_species(&centre, character.species).unwrap_or(centre);
+        let mut game = Game::new(Player::new(character, start.x, start.y), world);
+        game.update_visibility();
+        game
+    }
+
+    // The title menu, before any world has been generated
+    pub fn title_screen() -> Self {
+        let player = Player::new(Character::create_random(), 0, 0);
+        let mut game = Game::new(player, World::empty());
+        game.phase = GamePhase::Menu(MenuView::new(rand::random()));
+        game
+    }
+
+    // Replace the current run with a fresh one, keeping the settings
+    pub fn new_game(&mut self, seed: u32, width: usize, height: usize, character: Character) {
+        let mut game = Game::start(seed, width, height, character);
+        if let Some(hook) = &self.on_new_world
+            && let Err(e) = hook(&game.world)
+        {
+            game.set_message(format!("Could not dump the world: {e}"));
+        }
+        self.replace_run(game);
+    }
+
+    // Switch to another run, e.g. a loaded one. Key bindings and hooks are
+    // settings, not part of a run.
+    pub fn replace_run(&mut self, mut game: Game) {
+        std::mem::swap(&mut game.keymap, &mut self.keymap);
+        game.on_new_world = self.on_new_world.take();
+        *self = game;
+    }
+
+    // End the run and show how it went
+    pub fn end_run(&mut self, cause: &str) {
+        self.auto_travel = None;
+        self.clear_message();
+        self.phase = GamePhase::GameOver(GameSummary {
+            name: self.player.character.name.clone(),
+            days: self.clock.day(),
+            places: self.journal.places.len(),
+            xp: self.player.character.xp,
+            level: self.player.character.level,
+            cause: cause.to_string(),
+        });
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self.phase,
+            GamePhase::PlayingWorld
+                | GamePhase::PlayingLocation(_)
+                | GamePhase::Map(_)
+                | GamePhase::Journal(_)
+                | GamePhase::Combat(_)
+                | GamePhase::CharacterSheet(_)
+                | GamePhase::Inventory(_)
+                | GamePhase::Feature(_)
+                | GamePhase::Trade(_)
+                | GamePhase::Reputation(_)
+        )
+    }
+
     // Location-related methods
     pub fn enter_location(&mut self) {
         let current_tile = self.world.get_tile(&self.player.world_pos);
-        if let Some(location) = &current_tile.location {
+        if let Some(location) = current_tile.known_location() {
+            // Only the living keep watch at the gates
+            let inhabited = matches!(location.state, LocationState::Thriving | LocationState::Struggling);
+            let visitor = self.player.character.species;
+            let hosts = format!("The {}s of {}", location.species, location.name);
+            let welcome = match self.disposition(location) {
+                _ if !inhabited => None,
+                Attitude::Hostile if location.species.attitude_towards(visitor) == Attitude::Hostile => {
+                    self.set_message(format!("{hosts} will not let {visitor}s through their gates."));
+                    return;
+                }
+                Attitude::Hostile => {
+                    self.set_message(format!("{hosts} know of you and will not let you through their gates."));
+                    return;
+                }
+                Attitude::Friendly if location.species != visitor => Some(format!("{hosts} welcome you warmly.")),
+                Attitude::Wary => Some(format!("{hosts} eye you warily.")),
+                _ => None,
+            };
+
+            let seed = location_seed(self.world.seed, &[self.player.world_pos.x, self.player.world_pos.y]);
             let mut generator = LocationGenerator::new(
-                self.world.seed,
+                seed,
                 current_tile.terrain,
                 location.clone()
             );
             
-            let location_map = generator.generate();
-            let spawn_pos = location_map.find_spawn_position();
+            let floors = generator.generate_floors();
+            let spawn_pos = floors[0].find_spawn_position();
             self.player.enter_location(spawn_pos.x, spawn_pos.y);
-            self.phase = GamePhase::PlayingLocation(location_map);
+            self.phase = GamePhase::PlayingLocation(LocationStack::new(floors));
+            if location.state == LocationState::Cursed && self.player.character.apply_effect(CURSED) {
+                self.set_message("A chill settles on you as you pass the threshold. You are cursed.".to_string());
+                return;
+            }
+            match welcome {
+                Some(message) => self.set_message(message),
+                None => self.clear_message(),
+            }
+        }
+    }
+
+    // Enter the building or feature at `building_pos` in the current location map
+    pub fn enter_building(&mut self, building_pos: Position) {
+        let GamePhase::PlayingLocation(ref mut stack) = self.phase else {
+            return;
+        };
+
+        let tile = &stack.current().tiles[building_pos.y][building_pos.x];
+        let Some(kind) = BuildingKind::for_tile(tile) else {
+            return;
+        };
+
+        let world_pos = self.player.world_pos;
+        let seed = location_seed(
+            self.world.seed,
+            &[world_pos.x, world_pos.y, building_pos.x, building_pos.y, stack.depth()],
+        );
+        let floors = InteriorGenerator::new(seed, kind).generate_floors();
+        let spawn_pos = floors[0].find_entrance();
+
+        stack.push(floors, self.player.local_pos);
+        self.player.enter_location(spawn_pos.x, spawn_pos.y);
+        self.set_message(format!("You step inside the {}.", kind.name()));
+    }
+
+    // Take the stairs under the player, up when `up` is true
+    pub fn use_stairs(&mut self, up: bool) {
+        let GamePhase::PlayingLocation(ref mut stack) = self.phase else {
+            return;
+        };
+
+        let pos = self.player.local_pos;
+        let wanted = if up { LocationTileType::StairsUp } else { LocationTileType::StairsDown };
+        if stack.current().tiles[pos.y][pos.x].tile_type != wanted {
+            let msg = if up { "There are no stairs up here." } else { "There are no stairs down here." };
+            self.set_message(msg.to_string());
+            return;
+        }
+
+        if stack.change_floor(if up { 1 } else { -1 }) {
+            let map = stack.current();
+            let message = match map.level {
+                0 => format!("You return to the {}.", map.name),
+                level if level < 0 => format!("You descend into the {} (level {}).", map.name, level),
+                level => format!("You climb up to the {} (level {}).", map.name, level),
+            };
+            self.set_message(message);
         }
     }
 
     pub fn exit_location(&mut self) {
+        if let GamePhase::PlayingLocation(ref mut stack) = self.phase {
+            if stack.current().level != 0 {
+                self.set_message("You need to find the stairs back to ground level first.".to_string());
+                return;
+            }
+            if let Some(return_pos) = stack.pop() {
+                // Back out onto the exact tile we entered from
+                self.player.local_pos = return_pos;
+                self.clear_message();
+                return;
+            }
+        }
         self.player.exit_location();
         self.phase = GamePhase::PlayingWorld;
     }
 
+    // Recompute what the player can see wherever they currently are
+    pub fn update_visibility(&mut self) {
+        let bonus = self.sight_bonus();
+        match self.phase {
+            GamePhase::PlayingLocation(ref mut stack) => {
+                stack.current_mut().update_visibility(&self.player.local_pos, self.view_radius + bonus);
+            }
+            GamePhase::PlayingWorld => {
+                self.world.update(&self.player.world_pos, bonus);
+                self.reveal_hidden();
+                self.record_discoveries();
+            }
+            _ => {}
+        }
+    }
+
+    // Extra tiles of sight from the player's species and the time of day
+    pub fn sight_bonus(&self) -> i32 {
+        let traits = self.player.character.species.traits();
+        let darkness = if self.clock.is_night() && !traits.night_vision { NIGHT_SIGHT_PENALTY } else { 0 };
+        traits.vision - darkness
+    }
+
+    // How long crossing one tile of `terrain` takes the player
+    pub fn travel_time(&self, terrain: TerrainType, diagonal: bool) -> u64 {
+        let usual = world_step_time(terrain.move_cost(), diagonal);
+        self.player.character.species.traits().travel_time(terrain, usual)
+    }
+
+    // Hidden settlements on or next to the player's tile show themselves to
+    // those their people are friendly with
+    fn reveal_hidden(&mut self) {
+        let here = self.player.world_pos;
+        for (dx, dy) in [(0, 0), (-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+            let Some(pos) = self.world.neighbour(&here, dx, dy) else {
+                continue;
+            };
+            let trusted = self.world.get_tile(&pos).location.as_ref().is_some_and(|location| {
+                location.concealed() && self.disposition(location) == Attitude::Friendly
+            });
+            if let Some(location) = self.world.tiles[pos.y][pos.x].location.as_mut()
+                && trusted
+            {
+                location.revealed = true;
+                let message = format!(
+                    "The {}s of {} trust you enough to show you their hidden settlement.",
+                    location.species, location.name
+                );
+                self.set_message(message);
+            }
+        }
+    }
+
+    // How the people of `location` treat the player
+    pub fn disposition(&self, location: &Location) -> Attitude {
+        let attitude = location.species.attitude_towards(self.player.character.species);
+        disposition(attitude, self.player.reputation.standing(Faction::of(location)))
+    }
+
+    // Slaying a monster near a town earns its people's thanks. Returns a
+    // note on it.
+    fn defended_settlement(&mut self) -> Option<String> {
+        let here = self.player.world_pos;
+        let town = self.world.find_nearest_location(&here, |_, location| {
+            matches!(location.state, LocationState::Thriving | LocationState::Struggling)
+        })?;
+        if self.world.distance(&here, &town) > DEFENCE_RANGE {
+            return None;
+        }
+        let name = self.world.get_tile(&town).location.as_ref()?.name.clone();
+        let note = format!("Word of it reaches {}.", name);
+        match self.change_reputation(town, DEFENCE_STANDING) {
+            Some(change) => Some(format!("{} {}", note, change)),
+            None => Some(note),
+        }
+    }
+
+    // How the people of the settlement the player is in treat them
+    pub fn local_disposition(&self) -> Option<Attitude> {
+        let location = self.world.get_tile(&self.player.world_pos).location.as_ref()?;
+        Some(self.disposition(location))
+    }
+
+    // Every faction the player has dealt with or whose settlements are in
+    // the journal, with the player's standing, best regarded first
+    pub fn known_factions(&self) -> Vec<(Faction, i32)> {
+        let mut factions: Vec<(Faction, i32)> =
+            self.player.reputation.factions().iter().map(|entry| (entry.faction, entry.value)).collect();
+        for place in &self.journal.places {
+            let Some(location) = &self.world.get_tile(&place.position).location else {
+                continue;
+            };
+            let faction = Faction::of(location);
+            if !factions.iter().any(|(known, _)| *known == faction) {
+                factions.push((faction, 0));
+            }
+        }
+        factions.sort_by_key(|&(_, value)| -value);
+        factions
+    }
+
+    // What someone says when the player walks up to them
+    pub fn greet(&mut self, role: NpcRole) {
+        let Some(disposition) = self.local_disposition() else {
+            return;
+        };
+        let name = role.name();
+        let message = match disposition {
+            Attitude::Friendly => format!("The {name} greets you like an old friend."),
+            Attitude::Neutral => format!("The {name} nods to you."),
+            Attitude::Wary => format!("The {name} eyes you warily and keeps their distance."),
+            Attitude::Hostile => format!("The {name} glares at you and turns away."),
+        };
+        self.set_message(message);
+    }
+
+    // Raise or lower the player's standing with the people of the settlement
+    // at `pos`. Returns a note when their view of the player changes.
+    fn change_reputation(&mut self, pos: Position, amount: i32) -> Option<String> {
+        let faction = Faction::of(self.world.get_tile(&pos).location.as_ref()?);
+        self.player.reputation.change(faction, amount)
+    }
+
+    // Note every settlement in view that the journal doesn't know about yet
+    fn record_discoveries(&mut self) {
+        // What can be seen from home is known already and earns nothing
+        let at_home = self.journal.places.is_empty();
+        let mut discovered = 0;
+        for pos in &self.world.visible {
+            if let Some(location) = self.world.get_tile(pos).known_location()
+                && self.journal.record_place(*pos, location)
+            {
+                discovered += 1;
+            }
+        }
+        if discovered > 0
+            && !at_home
+            && let Some(note) = self.gain_xp(discovered * DISCOVERY_XP)
+        {
+            self.set_message(format!("Your travels have taught you much. {}", note));
+        }
+    }
+
+    // Award experience. Returns a note on any level gained, to go after
+    // whatever message says where the XP came from.
+    pub fn gain_xp(&mut self, amount: i32) -> Option<String> {
+        let character = &mut self.player.character;
+        if character.gain_xp(amount) == 0 {
+            return None;
+        }
+        Some(format!(
+            "You reach level {}! Press [{}] to spend {} attribute points.",
+            character.level,
+            self.keymap.key_label(KeyContext::World, Command::CharacterSheet),
+            character.unspent_points
+        ))
+    }
+
+    // Open the character sheet on top of the current screen
+    pub fn open_character_sheet(&mut self) {
+        let previous = std::mem::replace(&mut self.phase, GamePhase::Exit);
+        self.phase = GamePhase::CharacterSheet(CharacterSheet::new(previous));
+    }
+
+    pub fn open_reputation(&mut self) {
+        let previous = std::mem::replace(&mut self.phase, GamePhase::Exit);
+        self.phase = GamePhase::Reputation(ReputationView::new(previous));
+    }
+
+    pub fn open_inventory(&mut self) {
+        let previous = std::mem::replace(&mut self.phase, GamePhase::Exit);
+        self.phase = GamePhase::Inventory(InventoryView::new(previous));
+    }
+
+    // Leave the character sheet, inventory or feature for the screen it was opened from
+    pub fn close_screen(&mut self) {
+        let previous = match std::mem::replace(&mut self.phase, GamePhase::Exit) {
+            GamePhase::CharacterSheet(sheet) => sheet.previous,
+            GamePhase::Inventory(view) => view.previous,
+            GamePhase::Feature(view) => view.previous,
+            GamePhase::Trade(view) => view.previous,
+            GamePhase::Reputation(view) => view.previous,
+            phase => {
+                self.phase = phase;
+                return;
+            }
+        };
+        self.phase = *previous;
+        self.clear_message();
+    }
+
+    // Markers
+    pub fn set_marker(&mut self, position: Position, note: String) {
+        let note = note.trim().to_string();
+        if note.is_empty() {
+            self.set_message("A marker needs a note.".to_string());
+            return;
+        }
+        self.journal.set_marker(position, note);
+        self.set_message(format!("Marker placed at ({}, {}).", position.x, position.y));
+    }
+
+    pub fn remove_marker(&mut self, position: Position) {
+        if self.journal.remove_marker(&position) {
+            self.set_message(format!("Marker at ({}, {}) removed.", position.x, position.y));
+        }
+    }
+
+    pub fn survey(&mut self) {
+        let bonus = self.sight_bonus();
+        if self.world.survey(&self.player.world_pos, bonus) {
+            self.set_message("You climb to the summit and survey the land around you.".to_string());
+        } else {
+            self.set_message("You need to stand on a summit to survey the region.".to_string());
+        }
+    }
+
+    // Advance everything that happens without the player pressing a key.
+    // Called once per tick of the game loop.
+    pub fn tick(&mut self) {
+        if self.is_running() {
+            self.simulate_settlements();
+            self.run_effects();
+        }
+        if self.is_travelling() {
+            self.step_auto_travel();
+            self.update_visibility();
+        }
+        if self.is_running() && self.player.character.health <= 0 {
+            self.end_run("Succumbed to their wounds");
+        }
+    }
+
+    // Catch the world's settlements up with the clock, a day at a time. The
+    // place the player is at doesn't change under their feet.
+    pub fn simulate_settlements(&mut self) {
+        let here = self.player.world_pos;
+        while self.simulated_day < self.clock.day() {
+            for position in simulate_day(&mut self.world, self.simulated_day, Some(here)) {
+                if let Some(location) = &self.world.get_tile(&position).location {
+                    self.journal.update_place(position, location);
+                }
+            }
+            self.simulated_day += 1;
+        }
+    }
+
+    // Let status effects run a turn for every hour on the clock since they
+    // last did, however it was spent. Adds a note to the message for each
+    // one that wears off.
+    pub fn run_effects(&mut self) {
+        let mut news = Vec::new();
+        while self.clock.seconds >= self.effects_time + EFFECT_TURN_SECONDS {
+            news.extend(self.player.character.tick_effects().iter().map(|name| wore_off(name)));
+            self.effects_time += EFFECT_TURN_SECONDS;
+        }
+        if !news.is_empty() {
+            self.add_note(news.join(" "));
+        }
+    }
+
+    // Auto-travel
+    pub fn is_travelling(&self) -> bool {
+        self.auto_travel.is_some()
+    }
+
+    pub fn travel_to(&mut self, destination: Position) {
+        if !self.world.get_tile(&destination).seen {
+            self.set_message("You don't know the way into unexplored land.".to_string());
+            return;
+        }
+        let path = find_path(&self.world, self.player.world_pos, destination);
+        self.start_travel(path, "You can't find a way there.");
+    }
+
+    pub fn travel_to_nearest_settlement(&mut self) {
+        let path = find_nearest(&self.world, self.player.world_pos, |pos| {
+            let tile = self.world.get_tile(pos);
+            tile.seen && tile.known_location().is_some()
+        });
+        self.start_travel(path, "You know of no other settlement you could reach.");
+    }
+
+    pub fn explore(&mut self) {
+        let path = find_nearest(&self.world, self.player.world_pos, |pos| {
+            !self.world.get_tile(pos).seen
+        });
+        self.start_travel(path, "There is nothing left to explore from here.");
+    }
+
+    fn start_travel(&mut self, path: Option<Vec<Position>>, failure: &str) {
+        match path {
+            Some(path) if !path.is_empty() => {
+                let destination = path[path.len() - 1];
+                self.auto_travel = Some(AutoTravel { destination, path: path.into() });
+                self.clear_message();
+            }
+            _ => self.set_message(failure.to_string()),
+        }
+    }
+
+    pub fn cancel_travel(&mut self) {
+        self.auto_travel = None;
+    }
+
+    // Take the next step of the journey. Travel stops on arrival, when a new
+    // settlement comes into view, or when anything else raises a message.
+    pub fn step_auto_travel(&mut self) {
+        let Some(next) = self.auto_travel.as_mut().and_then(|travel| travel.path.pop_front()) else {
+            self.auto_travel = None;
+            return;
+        };
+        if self.world.get_tile(&next).blocked {
+            // Find another way round, or give up if there is none
+            let destination = self.auto_travel.take().map(|travel| travel.destination);
+            let detour = destination.and_then(|destination| find_path(&self.world, self.player.world_pos, destination));
+            self.start_travel(detour, "Your way is blocked.");
+            return;
+        }
+
+        let settlements_in_view = self.visible_settlements();
+        let diagonal = self.player.world_pos.x != next.x && self.player.world_pos.y != next.y;
+        self.clock.advance(self.travel_time(self.world.get_tile(&next).terrain, diagonal));
+        self.player.world_pos = next;
+        let bonus = self.sight_bonus();
+        self.world.update(&self.player.world_pos, bonus);
+        self.update_interaction_prompt();
+        self.world_step_taken();
+
+        if self.current_message.is_some() || self.phase != GamePhase::PlayingWorld {
+            self.auto_travel = None;
+            return;
+        }
+        let spotted = self.visible_settlements()
+            .into_iter()
+            .find(|pos| !settlements_in_view.contains(pos));
+        if let Some(pos) = spotted {
+            self.auto_travel = None;
+            if let Some(location) = self.world.get_tile(&pos).known_location() {
+                self.set_message(format!("You spot a {} settlement in the distance.", location.species));
+            }
+            return;
+        }
+        if self.auto_travel.as_ref().is_some_and(|travel| travel.path.is_empty()) {
+            self.auto_travel = None;
+            self.set_message("You arrive at your destination.".to_string());
+        }
+    }
+
+    fn visible_settlements(&self) -> HashSet<Position> {
+        self.world.visible
+            .iter()
+            .filter(|pos| self.world.get_tile(pos).known_location().is_some())
+            .copied()
+            .collect()
+    }
+
+    pub fn camp(&mut self) {
+        self.player.character.rest();
+        self.clock.advance_hours(CAMP_HOURS);
+        self.awake_since = self.clock.seconds;
+        self.set_message(format!("You make camp and rest for {} hours. It is now {}.", CAMP_HOURS, self.clock.describe()));
+    }
+
+    // Everything that happens after a step on the world map: cooldowns run
+    // down and monsters may notice the player
+    pub fn world_step_taken(&mut self) {
+        self.player.character.tick_cooldowns();
+        self.run_effects();
+        if self.clock.hours_since(self.awake_since) >= EXHAUSTION_HOURS && self.player.character.apply_effect(EXHAUSTED) {
+            self.add_note("You are exhausted. Make camp to rest.".to_string());
+        }
+
+        if self.unseen_steps > 0 {
+            self.unseen_steps -= 1;
+            return;
+        }
+
+        let tile = self.world.get_tile(&self.player.world_pos);
+        if tile.location.is_some() || tile.terrain == TerrainType::Road {
+            return;
+        }
+        if self.rng.gen_range(0..100) >= self.encounter_chance {
+            return;
+        }
+        let monsters = monsters_in(tile.terrain);
+        if monsters.is_empty() {
+            return;
+        }
+        let monster = monsters[self.rng.gen_range(0..monsters.len())];
+        self.start_combat(Enemy::from(monster));
+    }
+
+    // Combat
+    pub fn start_combat(&mut self, enemy: Enemy) {
+        self.auto_travel = None;
+        self.ability_menu = None;
+        self.clear_message();
+        self.phase = GamePhase::Combat(Combat::new(enemy));
+    }
+
+    // The player's turn in a fight, followed by the enemy's
+    pub fn combat_action(&mut self, action: CombatAction) {
+        let GamePhase::Combat(ref mut combat) = self.phase else {
+            return;
+        };
+        let character = &mut self.player.character;
+        if let CombatAction::Ability(index) = action
+            && let Some(reason) = character.ability_unavailable(index)
+        {
+            // Trying costs nothing, not even the turn
+            combat.log(reason);
+            return;
+        }
+        let enemy = &mut combat.enemy;
+        let mut lines = Vec::new();
+        let mut escaped = None;
+
+        match action {
+            CombatAction::Attack => match roll_attack(&mut self.rng, character.attack(), enemy.dodge) {
+                Some(damage) => {
+                    enemy.health -= damage;
+                    lines.push(format!("You hit the {} for {} damage.", enemy.name, damage));
+                }
+                None => lines.push(format!("You miss the {}.", enemy.name)),
+            },
+            CombatAction::Ability(index) => {
+                let ability = &character.abilities()[index];
+                character.spend_ability(index);
+                match ability.effect {
+                    AbilityEffect::Strike { multiplier } => {
+                        match roll_attack(&mut self.rng, character.attack(), enemy.dodge) {
+                            Some(damage) => {
+                                enemy.health -= damage * multiplier;
+                                lines.push(format!(
+                                    "Your {} hits the {} for {} damage.",
+                                    ability.name, enemy.name, damage * multiplier
+                                ));
+                            }
+                            None => lines.push(format!("Your {} misses the {}.", ability.name, enemy.name)),
+                        }
+                    }
+                    AbilityEffect::Bolt { damage } => {
+                        let damage = damage + character.spell_power();
+                        enemy.health -= damage;
+                        lines.push(format!("Your {} sears the {} for {} damage.", ability.name, enemy.name, damage));
+                    }
+                    AbilityEffect::Heal { amount } => {
+                        let restored = character.restore_health(amount + character.spell_power());
+                        lines.push(format!("Your {} restores {} health.", ability.name, restored));
+                    }
+                    AbilityEffect::Stealth { steps } => {
+                        self.unseen_steps = steps;
+                        escaped = Some(format!("You melt into the shadows and leave the {} behind.", enemy.name));
+                    }
+                }
+            }
+            CombatAction::Flee => {
+                if self.rng.gen_range(0..100) < hit_chance(character.dodge(), enemy.attack) {
+                    escaped = Some(format!("You get away from the {}.", enemy.name));
+                } else {
+                    lines.push("You fail to get away.".to_string());
+                }
+            }
+        }
+
+        if let Some(message) = escaped {
+            self.phase = GamePhase::PlayingWorld;
+            self.set_message(message);
+            return;
+        }
+        if enemy.health <= 0 {
+            let (name, xp) = (enemy.name.clone(), enemy.xp);
+            let gold = self.rng.gen_range(0..=enemy.gold);
+            let broken = character.wear_gear(WEAR_PER_FIGHT);
+            self.phase = GamePhase::PlayingWorld;
+            self.player.gold += gold;
+            let mut message = match gold {
+                0 => format!("You defeat the {} and gain {} XP.", name, xp),
+                gold => format!("You defeat the {} and gain {} XP and {} gold.", name, xp, gold),
+            };
+            for item in broken {
+                message = format!("{} Your {} breaks.", message, item);
+            }
+            if let Some(note) = self.defended_settlement() {
+                message = format!("{} {}", message, note);
+            }
+            if let Some(note) = self.gain_xp(xp) {
+                message = format!("{} {}", message, note);
+            }
+            self.set_message(message);
+            return;
+        }
+
+        // The enemy strikes back
+        match roll_attack(&mut self.rng, enemy.attack, character.dodge()) {
+            Some(damage) => {
+                character.health -= damage;
+                lines.push(format!("The {} hits you for {} damage.", enemy.name, damage));
+                if let Some(affliction) = &enemy.inflicts
+                    && self.rng.gen_range(0..100) < affliction.chance
+                    && character.apply_effect(&affliction.effect)
+                {
+                    lines.push(format!("You are {}.", affliction.effect.to_lowercase()));
+                }
+            }
+            None => lines.push(format!("The {} misses you.", enemy.name)),
+        }
+        character.tick_cooldowns();
+        lines.extend(character.tick_effects().iter().map(|name| wore_off(name)));
+        let cause = format!("Slain by a {}", enemy.name);
+        for line in lines {
+            combat.log(line);
+        }
+        if character.health <= 0 {
+            self.end_run(&cause);
+        }
+    }
+
+    // Use a class ability outside of combat
+    pub fn use_ability(&mut self, index: usize) {
+        let character = &mut self.player.character;
+        let Some(ability) = character.abilities().get(index) else {
+            return;
+        };
+        if ability.effect.combat_only() {
+            self.set_message(format!("There is nothing here to use {} on.", ability.name));
+            return;
+        }
+        if let Some(reason) = character.ability_unavailable(index) {
+            self.set_message(reason);
+            return;
+        }
+        character.spend_ability(index);
+        let message = match ability.effect {
+            AbilityEffect::Heal { amount } => {
+                let restored = character.restore_health(amount + character.spell_power());
+                format!("Your {} restores {} health.", ability.name, restored)
+            }
+            AbilityEffect::Stealth { steps } => {
+                self.unseen_steps = steps;
+                "You move on unseen; nothing will notice you for a while.".to_string()
+            }
+            AbilityEffect::Strike { .. } | AbilityEffect::Bolt { .. } => return,
+        };
+        self.set_message(message);
+    }
+
     // Message handling methods
     pub fn update_interaction_prompt(&mut self) {
         let current_tile = self.world.get_tile(&self.player.world_pos);
@@ -61,32 +987,382 @@ impl Game {
         self.current_message = None;
     }
 
-    pub fn handle_interaction(&mut self) {
-        let current_tile = self.world.get_tile(&self.player.world_pos);
-        if let Some(location) = &current_tile.location {
-            let state = location.state;
-            let species = location.species;
-            
-            let message = match state {
-                LocationState::Thriving | LocationState::Struggling => {
-                    format!("Entering the {} settlement of {}s", 
-                        state.to_string().to_lowercase(),
-                        species.to_string())
+    // What the player can do where they stand: the state of the place
+    // they are at decides some verbs, features close by the rest
+    pub fn available_interactions(&self) -> Vec<Interaction> {
+        let mut interactions = Vec::new();
+        if let Some(location) = self.world.get_tile(&self.player.world_pos).known_location() {
+            match location.state {
+                LocationState::Sacred => interactions.push(Interaction::Pray),
+                LocationState::Cursed => interactions.push(Interaction::Cleanse),
+                LocationState::Abandoned | LocationState::Ruins | LocationState::Hidden if !location.searched => {
+                    interactions.push(Interaction::Search)
+                }
+                _ => {}
+            }
+        }
+        let features = self.nearby_features();
+        // Temples and taverns keep the usual keys for praying and resting
+        for feature in &features {
+            for service in feature_services(feature.feature_type) {
+                let shortcut = match service {
+                    Service::Bless { .. } => Interaction::Pray,
+                    Service::Rest { .. } => Interaction::Rest,
+                    _ => continue,
+                };
+                if !interactions.contains(&shortcut) {
+                    interactions.push(shortcut);
+                }
+            }
+        }
+        if !features.is_empty() {
+            interactions.push(Interaction::Visit);
+        }
+        let market = features.iter().any(|feature| feature.feature_type == FeatureType::Market);
+        if market || self.near_trading_post() {
+            interactions.push(Interaction::Trade);
+        }
+        interactions
+    }
+
+    // Whether a trading post stands on or next to the player's tile
+    fn near_trading_post(&self) -> bool {
+        let GamePhase::PlayingLocation(stack) = &self.phase else {
+            return false;
+        };
+        let map = stack.current();
+        let pos = self.player.local_pos;
+        (-1..=1).any(|dy| {
+            (-1..=1).any(|dx| {
+                let (x, y) = (pos.x as i32 + dx, pos.y as i32 + dy);
+                map.in_bounds(x, y) && map.tiles[y as usize][x as usize].tile_type == LocationTileType::Trading
+            })
+        })
+    }
+
+    // Prices in the settlement the player is in, or None out in the wild
+    pub fn local_market(&self) -> Option<Market> {
+        let pos = self.player.world_pos;
+        let location = self.world.get_tile(&pos).location.as_ref()?;
+        let standing = self.player.reputation.get(Faction::of(location));
+        Some(Market::new(self.world.seed, pos, location).with_standing(standing))
+    }
+
+    // Everything a feature of kind `feature` in this settlement offers
+    pub fn feature_offers(&self, feature: FeatureType) -> Vec<Offer> {
+        match self.local_market() {
+            Some(market) => feature_offers(feature, &self.player.character, &market, &self.clock),
+            None => Vec::new(),
+        }
+    }
+
+    // Features with something to offer on or next to the player's tile,
+    // the one underfoot first
+    pub fn nearby_features(&self) -> Vec<&Feature> {
+        let GamePhase::PlayingLocation(stack) = &self.phase else {
+            return Vec::new();
+        };
+        let pos = self.player.local_pos;
+        let mut nearby: Vec<_> = stack
+            .current()
+            .points_of_interest
+            .iter()
+            .filter(|poi| poi.position.x.abs_diff(pos.x) <= 1 && poi.position.y.abs_diff(pos.y) <= 1)
+            .filter(|poi| !feature_services(poi.feature.feature_type).is_empty())
+            .collect();
+        nearby.sort_by_key(|poi| poi.position != pos);
+        nearby.into_iter().map(|poi| &poi.feature).collect()
+    }
+
+    // The feature the player visits when there is more than one close by
+    pub fn nearby_feature(&self) -> Option<&Feature> {
+        self.nearby_features().first().copied()
+    }
+
+    // The first offer from the features close by that `wanted` accepts
+    fn nearby_offer(&self, wanted: impl Fn(&Deal) -> bool) -> Option<Offer> {
+        self.nearby_features()
+            .iter()
+            .flat_map(|feature| self.feature_offers(feature.feature_type))
+            .find(|offer| wanted(&offer.deal))
+    }
+
+    pub fn handle_interaction(&mut self, interaction: Interaction) {
+        if !self.available_interactions().contains(&interaction) {
+            self.set_message(format!("There is nothing here to {}.", interaction.label().to_lowercase()));
+            return;
+        }
+        let pos = self.player.world_pos;
+        let Some(location) = self.world.tiles[pos.y][pos.x].location.as_mut() else {
+            return;
+        };
+        let character = &mut self.player.character;
+
+        let message = match interaction {
+            Interaction::Pray if location.state == LocationState::Sacred => {
+                // Prayer lifts curses and brings a blessing
+                self.clock.advance_hours(PRAYER_HOURS);
+                let lifted = character.remove_effect(CURSED);
+                character.apply_effect(BLESSED);
+                let lifted = if lifted { " Your curse is lifted." } else { "" };
+                format!("You pray at the sacred site of the {}s and feel blessed.{}", location.species, lifted)
+            }
+            Interaction::Pray | Interaction::Rest => {
+                let wanted = if interaction == Interaction::Pray { Deal::Bless } else { Deal::Rest };
+                if let Some(offer) = self.nearby_offer(|deal| *deal == wanted) {
+                    self.make_deal(offer);
                 }
-                LocationState::Abandoned | LocationState::Ruins => {
-                    format!("Exploring the {} ruins", species.to_string())
+                return;
+            }
+            Interaction::Visit => {
+                if let Some(feature) = self.nearby_feature().cloned() {
+                    self.open_feature(feature);
                 }
-                LocationState::Sacred => {
-                    format!("You pray at the sacred site of the {}", species.to_string())
+                return;
+            }
+            Interaction::Trade => {
+                self.open_trade(market_wares().to_vec());
+                return;
+            }
+            Interaction::Cleanse => {
+                self.clock.advance_hours(CLEANSE_HOURS);
+                let chance = cleanse_chance(character);
+                if self.rng.gen_range(0..100) >= chance {
+                    character.apply_effect(CURSED);
+                    self.set_message("You attempt to cleanse this cursed place, but the curse clings to you.".to_string());
+                    return;
                 }
-                LocationState::Cursed => {
-                    "You attempt to cleanse this cursed place".to_string()
+                character.remove_effect(CURSED);
+                location.state = LocationState::Abandoned;
+                self.journal.update_place(pos, location);
+                let mut message = format!(
+                    "The curse on {} breaks and you gain {} XP. The place lies quiet and empty now.",
+                    location.name, CLEANSE_XP
+                );
+                if let Some(note) = self.gain_xp(CLEANSE_XP) {
+                    message = format!("{} {}", message, note);
                 }
-                LocationState::Hidden => {
-                    "You investigate the hidden location".to_string()
+                if let Some(note) = self.change_reputation(pos, CLEANSE_STANDING) {
+                    message = format!("{} {}", message, note);
                 }
-            };
-            self.set_message(message);
+                message
+            }
+            Interaction::Search => {
+                self.clock.advance_hours(SEARCH_HOURS);
+                location.searched = true;
+                let hidden = location.state == LocationState::Hidden;
+                let chance = SEARCH_CHANCE + character.attribute(Attribute::Luck) * 5;
+                let mut message = if self.rng.gen_range(0..100) >= chance {
+                    "You search for hours but turn up nothing of use.".to_string()
+                } else {
+                    let item = Item::new(SALVAGE[self.rng.gen_range(0..SALVAGE.len())]);
+                    let message = format!(
+                        "You pick through the {} settlement and turn up: {}.",
+                        location.state.to_string(),
+                        item.name
+                    );
+                    character.inventory.push(item);
+                    message
+                };
+                // Hidden places aren't empty, and their people see who pries
+                if hidden {
+                    message = format!("{} Its people won't forget this.", message);
+                    if let Some(note) = self.change_reputation(pos, LOOTING_STANDING) {
+                        message = format!("{} {}", message, note);
+                    }
+                }
+                message
+            }
+        };
+        self.set_message(message);
+    }
+
+    // Take up an offer from a feature: pay for it, then get what was paid for
+    pub fn make_deal(&mut self, offer: Offer) {
+        let character = &self.player.character;
+        let refusal = match &offer.deal {
+            Deal::Heal if character.health >= character.max_health() => Some("You are not hurt.".to_string()),
+            Deal::Repair if character.gear_damage() == 0 => Some("Your gear is in good repair.".to_string()),
+            Deal::Train { attribute, .. } if character.attributes.get(*attribute) >= TRAINING_CAP => Some(format!(
+                "There is nothing more the trainers here can teach you about {}.",
+                attribute.label().to_lowercase()
+            )),
+            _ => None,
+        };
+        if let Some(refusal) = refusal {
+            self.set_message(refusal);
+            return;
+        }
+        // Trading is free to start; the trade screen handles the prices
+        if let Deal::Trade(wares) = offer.deal {
+            self.open_trade(wares);
+            return;
         }
+        let rumour = match offer.deal {
+            Deal::Rumours => {
+                let here = self.player.world_pos;
+                let journal = &self.journal;
+                let found = self.world.find_nearest_location(&here, |pos, _| {
+                    *pos != here && journal.place_at(pos).is_none() && !journal.markers.iter().any(|m| m.position == *pos)
+                });
+                let Some(found) = found else {
+                    self.set_message("Nobody here knows of a place you haven't heard of.".to_string());
+                    return;
+                };
+                Some(found)
+            }
+            _ => None,
+        };
+        if !self.player.spend_gold(offer.price) {
+            self.set_message(format!("You can't afford that. It costs {} gold and you have {}.", offer.price, self.player.gold));
+            return;
+        }
+
+        let character = &mut self.player.character;
+        let message = match offer.deal {
+            Deal::Buy(name) => {
+                character.inventory.push(Item::new(&name));
+                format!("You buy the {} for {} gold.", name, offer.price)
+            }
+            Deal::Heal => {
+                character.restore_health(character.max_health());
+                "The priests tend your wounds. You are fully healed.".to_string()
+            }
+            Deal::Bless => {
+                self.clock.advance_hours(PRAYER_HOURS);
+                let lifted = character.remove_effect(CURSED);
+                character.apply_effect(BLESSED);
+                let lifted = if lifted { " Your curse is lifted." } else { "" };
+                format!("You leave an offering of {} gold and pray. You feel blessed.{}", offer.price, lifted)
+            }
+            Deal::Rest => {
+                character.rest();
+                self.clock.advance_hours(CAMP_HOURS);
+                self.awake_since = self.clock.seconds;
+                format!(
+                    "You take a room at the tavern and sleep for {} hours. It is now {}.",
+                    CAMP_HOURS,
+                    self.clock.describe()
+                )
+            }
+            Deal::Rumours => {
+                let found = rumour.expect("a rumoured place was found above");
+                let here = self.player.world_pos;
+                let species = self.world.get_tile(&found).location.as_ref().map_or(Species::Human, |l| l.species);
+                let direction = self.world.compass(&here, &found);
+                self.journal.set_marker(found, format!("Rumoured {} settlement", species));
+                format!("Over a drink you hear of a {} settlement to the {}. It is marked on your map.", species, direction)
+            }
+            Deal::Repair => {
+                character.repair_gear();
+                "The smith hammers out the dents. Your gear is as good as new.".to_string()
+            }
+            Deal::Trade(_) => return,
+            Deal::Train { attribute, hours } => {
+                self.clock.advance_hours(hours);
+                character.raise_attribute(attribute);
+                format!(
+                    "After {} hours of drills your {} rises to {}.",
+                    hours,
+                    attribute.label().to_lowercase(),
+                    character.attributes.get(attribute)
+                )
+            }
+        };
+        self.set_message(message);
+        if offer.price > 0 {
+            self.note_custom();
+        }
+    }
+
+    // Business done in a settlement makes its people think better of the
+    // player; adds a note to the message when their view changes
+    fn note_custom(&mut self) {
+        if let Some(note) = self.change_reputation(self.player.world_pos, CUSTOM_STANDING) {
+            self.add_note(note);
+        }
+    }
+
+    // Tack `note` onto the end of the current message
+    fn add_note(&mut self, note: String) {
+        let message = match self.current_message.take() {
+            Some(message) => format!("{} {}", message, note),
+            None => note,
+        };
+        self.set_message(message);
     }
-}
\ No newline at end of file
+
+    pub fn open_feature(&mut self, feature: Feature) {
+        let previous = std::mem::replace(&mut self.phase, GamePhase::Exit);
+        self.phase = GamePhase::Feature(FeatureView::new(feature, previous));
+        self.clear_message();
+    }
+
+    // Open the trade screen for the settlement the player is in; `wares`
+    // are sold there besides its own goods
+    pub fn open_trade(&mut self, wares: Vec<String>) {
+        let pos = self.player.world_pos;
+        let (Some(market), Some(location)) = (self.local_market(), self.world.get_tile(&pos).location.as_ref()) else {
+            return;
+        };
+        let name = location.name.clone();
+        let previous = std::mem::replace(&mut self.phase, GamePhase::Exit);
+        self.phase = GamePhase::Trade(TradeView::new(name, market, wares, previous));
+        self.clear_message();
+    }
+
+    // Buy or sell the highlighted row of the trade screen
+    pub fn trade(&mut self) {
+        let GamePhase::Trade(view) = &mut self.phase else {
+            return;
+        };
+        let (message, traded) = match view.side {
+            TradeSide::Buy => {
+                let Some(item) = view.market.stock(&view.wares).into_iter().nth(view.selected) else {
+                    return;
+                };
+                let price = view.market.price(&item, &self.clock);
+                if self.player.spend_gold(price) {
+                    let message = format!("You buy the {} for {} gold.", item.name, price);
+                    self.player.character.inventory.push(item);
+                    (message, true)
+                } else {
+                    (format!("You can't afford that. It costs {} gold and you have {}.", price, self.player.gold), false)
+                }
+            }
+            TradeSide::Sell => {
+                let character = &mut self.player.character;
+                let Some(item) = character.inventory.get(view.selected) else {
+                    return;
+                };
+                let price = view.market.offer(item, &self.clock);
+                if price == 0 {
+                    (format!("The traders here have no use for your {}.", item.name), false)
+                } else {
+                    let item = character.inventory.remove(view.selected);
+                    view.clamp(character.inventory.len());
+                    self.player.gold += price;
+                    (format!("You sell the {} for {} gold.", item.name, price), true)
+                }
+            }
+        };
+        self.set_message(message);
+        if traded {
+            self.note_custom();
+        }
+    }
+}
+
+// Percent chance to break the curse on a place; faith and a sharp mind help
+fn cleanse_chance(character: &Character) -> u32 {
+    let mut chance = (character.attribute(Attribute::Intelligence) + character.attribute(Attribute::Luck)) * 3;
+    if character.has_effect(BLESSED) {
+        chance += BLESSED_CLEANSE_BONUS;
+    }
+    chance.clamp(5, 95) as u32
+}
+
+// e.g. "You are no longer poisoned."
+fn wore_off(effect: &str) -> String {
+    format!("You are no longer {}.", effect.to_lowercase())
+}
@@ -0,0 +1,30 @@
+use crate::core::game::{Game, GamePhase};
+use crate::core::input::handle_key;
+use crate::core::input_source::InputSource;
+use crate::prelude::Result;
+use crate::render::Renderer;
+use std::time::{Duration, Instant};
+
+// How often the game advances on its own, e.g. one step of auto-travel
+pub const TICK_RATE: Duration = Duration::from_millis(40);
+
+// Run until the player quits or the input runs dry. Key presses are handled as
+// they arrive; in between, the game ticks at a fixed rate.
+pub fn run(game: &mut Game, renderer: &mut dyn Renderer, input: &mut dyn InputSource) -> Result<()> {
+    let mut last_tick = Instant::now();
+
+    while game.phase != GamePhase::Exit && !input.is_finished() {
+        renderer.render(game)?;
+
+        let timeout = TICK_RATE.saturating_sub(last_tick.elapsed());
+        if let Some(key) = input.poll(timeout)? {
+            handle_key(game, key);
+        }
+
+        if last_tick.elapsed() >= TICK_RATE {
+            game.tick();
+            last_tick = Instant::now();
+        }
+    }
+    Ok(())
+}
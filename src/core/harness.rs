@@ -0,0 +1,972 @@
+// Headless harness for driving whole game sessions in tests: build a game
+// from a seed, feed it commands or key presses, and inspect the result.
+
+use crate::core::command::{Command, Direction};
+use crate::core::game::{Game, GamePhase};
+use crate::core::game_loop;
+use crate::core::input::{handle_command, handle_key};
+use crate::core::input_source::ScriptedInput;
+use crate::generators::location_generator::{FeatureType, LocationMap};
+use crate::prelude::Result;
+use crate::render::Renderer;
+use crate::render::tui_render::TuiRenderer;
+use crate::systems::character::{Attributes, Character};
+use crate::systems::position::Position;
+use crate::systems::location::Species;
+use crossterm::event::KeyCode;
+use ratatui::backend::TestBackend;
+use std::collections::{HashMap, VecDeque};
+
+// Small enough to generate quickly, big enough to hold a few settlements
+pub const WORLD_WIDTH: usize = 96;
+pub const WORLD_HEIGHT: usize = 64;
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 36;
+
+// Give up on auto-travel that takes longer than this many ticks
+const MAX_TRAVEL_TICKS: usize = 10_000;
+
+// A renderer that draws nothing
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn render(&mut self, _game: &Game) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Harness {
+    pub game: Game,
+}
+
+impl Harness {
+    // A human warrior starting in their home town
+    pub fn new(seed: u32) -> Self {
+        let character = Character::new("Tester".to_string(), "Warrior".to_string(), Species::Human, Attributes::point_buy());
+        Self::with_character(seed, character)
+    }
+
+    pub fn with_character(seed: u32, character: Character) -> Self {
+        let mut game = Game::start(seed, WORLD_WIDTH, WORLD_HEIGHT, character);
+        // No wandering monsters unless a test asks for them
+        game.encounter_chance = 0;
+        Self { game }
+    }
+
+    pub fn command(&mut self, command: Command) -> &mut Self {
+        handle_command(&mut self.game, command);
+        self
+    }
+
+    pub fn commands(&mut self, commands: &[Command]) -> &mut Self {
+        for &command in commands {
+            self.command(command);
+        }
+        self
+    }
+
+    // Press a key through the game's key bindings
+    pub fn key(&mut self, key: KeyCode) -> &mut Self {
+        handle_key(&mut self.game, key);
+        self
+    }
+
+    // Run the real game loop on a script of key names, e.g. "e Down q"
+    pub fn play(&mut self, script: &str) -> Result<&mut Self> {
+        let mut input = ScriptedInput::from_script(script)?;
+        game_loop::run(&mut self.game, &mut NullRenderer, &mut input)?;
+        Ok(self)
+    }
+
+    // Tick until auto-travel has finished. Returns false if it never does.
+    pub fn finish_travel(&mut self) -> bool {
+        for _ in 0..MAX_TRAVEL_TICKS {
+            if !self.game.is_travelling() {
+                return true;
+            }
+            self.game.tick();
+        }
+        !self.game.is_travelling()
+    }
+
+    // Walk inside the current location to `target`, one Move command per
+    // step. Returns false if there is no way there.
+    pub fn walk_to(&mut self, target: Position) -> bool {
+        let Some(steps) = self.location().and_then(|map| {
+            local_path(map, self.game.player.local_pos, target)
+        }) else {
+            return false;
+        };
+        for direction in steps {
+            self.command(Command::Move(direction));
+        }
+        self.game.player.local_pos == target
+    }
+
+    pub fn location(&self) -> Option<&LocationMap> {
+        match &self.game.phase {
+            GamePhase::PlayingLocation(stack) => Some(stack.current()),
+            _ => None,
+        }
+    }
+
+    pub fn location_mut(&mut self) -> Option<&mut LocationMap> {
+        match &mut self.game.phase {
+            GamePhase::PlayingLocation(stack) => Some(stack.current_mut()),
+            _ => None,
+        }
+    }
+
+    pub fn location_depth(&self) -> usize {
+        match &self.game.phase {
+            GamePhase::PlayingLocation(stack) => stack.depth(),
+            _ => 0,
+        }
+    }
+
+    pub fn feature_position(&self, feature: FeatureType) -> Option<Position> {
+        self.location()?
+            .points_of_interest
+            .iter()
+            .find(|poi| poi.feature.feature_type == feature)
+            .map(|poi| poi.position)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.game.current_message.as_deref()
+    }
+
+    // The current screen as text, drawn by the real renderer
+    pub fn screen(&self) -> String {
+        render_to_text(&self.game)
+    }
+}
+
+// Draw the game into an in-memory terminal and return its contents, one line
+// of text per row with trailing blanks trimmed
+pub fn render_to_text(game: &Game) -> String {
+    let backend = TestBackend::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let mut renderer = TuiRenderer::with_backend(backend).expect("test backend never fails");
+    renderer.render(game).expect("rendering into a buffer never fails");
+
+    let buffer = renderer.backend().buffer();
+    let mut text = String::new();
+    for y in 0..buffer.area.height {
+        let line: String = (0..buffer.area.width)
+            .map(|x| buffer.get(x, y).symbol())
+            .collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+// Breadth-first search over walkable tiles, as a list of straight steps
+fn local_path(map: &LocationMap, start: Position, goal: Position) -> Option<Vec<Direction>> {
+    let directions = [Direction::North, Direction::South, Direction::East, Direction::West];
+    let mut came_from: HashMap<Position, (Position, Direction)> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == goal {
+            let mut steps = Vec::new();
+            let mut pos = goal;
+            while let Some(&(previous, direction)) = came_from.get(&pos) {
+                steps.push(direction);
+                pos = previous;
+            }
+            steps.reverse();
+            return Some(steps);
+        }
+        for direction in directions {
+            let (dx, dy) = direction.delta();
+            let (x, y) = (current.x as i32 + dx, current.y as i32 + dy);
+            if !map.in_bounds(x, y) {
+                continue;
+            }
+            let next = Position::new(x as usize, y as usize);
+            let passable = map.is_walkable(next.x, next.y) && map.npc_at(&next).is_none();
+            if next == start || came_from.contains_key(&next) || (!passable && next != goal) {
+                continue;
+            }
+            came_from.insert(next, (current, direction));
+            if passable {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+mod tests {
+    use super::*;
+    use crate::core::command::Interaction;
+    use crate::systems::item::Item;
+    use crate::systems::player::STARTING_GOLD;
+    use crate::systems::services::Deal;
+    use crate::core::save::{load_from_str, save_to_string};
+    use crate::generators::location_generator::LocationTileType;
+    use crate::systems::character::{Attribute, Attributes, xp_for_level};
+    use crate::systems::status::{BLESSED, CURSED, EXHAUSTED};
+    use crate::systems::combat::{Enemy, monsters_in};
+    use crate::systems::equipment::EQUIP_SLOTS;
+    use crate::systems::world::TerrainType;
+    use crate::systems::location::{Industry, LocationState, PLAYABLE_SPECIES, Species};
+    use crate::systems::reputation::{Faction, MIN_STANDING, Standing};
+    use crate::systems::species::Attitude;
+
+    const SEED: u32 = 42;
+
+    // The game as it comes back from a save, without touching the disk
+    fn reloaded(game: &Game) -> Game {
+        load_from_str(&save_to_string(game).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn starts_in_a_town_of_the_players_species() {
+        let harness = Harness::new(SEED);
+        let game = &harness.game;
+        let tile = game.world.get_tile(&game.player.world_pos);
+        let location = tile.location.as_ref().expect("the player starts in a settlement");
+        assert_eq!(location.species, game.player.character.species);
+        // and it is already in the journal
+        assert!(game.journal.place_at(&game.player.world_pos).is_some());
+    }
+
+    #[test]
+    fn enter_the_town_and_walk_to_the_market() {
+        let mut harness = Harness::new(SEED);
+        harness.command(Command::Enter);
+        assert_eq!(harness.location_depth(), 1);
+
+        let market = harness.feature_position(FeatureType::Market).expect("towns have a market");
+        // Step away first so the walk actually goes somewhere
+        harness.commands(&[Command::Move(Direction::South), Command::Move(Direction::South)]);
+        assert_ne!(harness.game.player.local_pos, market);
+        let before = harness.game.clock;
+
+        assert!(harness.walk_to(market));
+        assert!(harness.game.clock.seconds > before.seconds);
+
+        harness.command(Command::Enter);
+        assert_eq!(harness.location_depth(), 2);
+        assert_eq!(harness.message(), Some("You step inside the market hall."));
+
+        harness.command(Command::Exit);
+        assert_eq!(harness.location_depth(), 1);
+        assert_eq!(harness.game.player.local_pos, market);
+
+        harness.command(Command::Exit);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+    }
+
+    #[test]
+    fn no_squeezing_diagonally_between_walls() {
+        let mut harness = Harness::new(SEED);
+        harness.command(Command::Enter);
+        let pos = harness.game.player.local_pos;
+
+        // Open ground to the north-east, walls to the north and east
+        let map = harness.location_mut().unwrap();
+        map.npc_spots.clear();
+        map.tiles[pos.y - 1][pos.x + 1].tile_type = LocationTileType::Ground;
+        map.tiles[pos.y - 1][pos.x].tile_type = LocationTileType::Wall;
+        map.tiles[pos.y][pos.x + 1].tile_type = LocationTileType::Wall;
+        harness.command(Command::Move(Direction::NorthEast));
+        assert_eq!(harness.game.player.local_pos, pos);
+
+        // With one wall gone there is room to pass
+        harness.location_mut().unwrap().tiles[pos.y][pos.x + 1].tile_type = LocationTileType::Ground;
+        harness.command(Command::Move(Direction::NorthEast));
+        assert_eq!(harness.game.player.local_pos, Position::new(pos.x + 1, pos.y - 1));
+    }
+
+    #[test]
+    fn map_opens_and_closes_with_the_same_key() {
+        let mut harness = Harness::new(SEED);
+        harness.key(KeyCode::Char('m'));
+        assert!(matches!(harness.game.phase, GamePhase::Map(_)));
+        assert!(harness.screen().contains("World Map"));
+        harness.key(KeyCode::Char('m'));
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+    }
+
+    #[test]
+    fn no_travelling_into_unexplored_land_from_the_map() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        harness.key(KeyCode::Char('m'));
+        for _ in 0..20 {
+            harness.command(Command::Move(Direction::East));
+        }
+        let GamePhase::Map(view) = &harness.game.phase else {
+            panic!("the map is open");
+        };
+        assert!(!harness.game.world.get_tile(&view.cursor).seen);
+        harness.command(Command::Confirm);
+        assert!(!harness.game.is_travelling());
+        assert_eq!(harness.message(), Some("You don't know the way into unexplored land."));
+        assert_eq!(harness.game.player.world_pos, home);
+    }
+
+    #[test]
+    fn quitting_from_a_location_ends_the_game() {
+        let mut harness = Harness::new(SEED);
+        harness.play("e Down Q").unwrap();
+        let GamePhase::GameOver(summary) = &harness.game.phase else {
+            panic!("quitting should show the game-over screen");
+        };
+        assert_eq!(summary.days, 1);
+        assert_eq!(summary.places, harness.game.journal.places.len());
+        assert!(harness.screen().contains("Retired from adventuring"));
+
+        // Back to the title, then quit from there
+        harness.play("Enter").unwrap();
+        assert!(matches!(harness.game.phase, GamePhase::Menu(_)));
+        harness.play("Q").unwrap();
+        assert!(harness.game.phase == GamePhase::Exit);
+    }
+
+    #[test]
+    fn new_game_from_the_title_menu() {
+        let mut harness = Harness { game: Game::title_screen() };
+        harness.play("Enter").unwrap();
+        // Replace the random seed with one typed in, then pick the small world
+        if let GamePhase::Menu(view) = &mut harness.game.phase {
+            view.seed = "4".to_string();
+        }
+        harness.play("Backspace 4 2 Down Left Left Enter").unwrap();
+        assert!(harness.screen().contains("New Character"));
+
+        // Named Bramble, an Elf Rogue with everything spent on strength
+        harness.play("B r a m b l e Down Right Down Right Right Down Down Right Right Right Right Right Right Right").unwrap();
+        let GamePhase::Menu(view) = &harness.game.phase else {
+            panic!("still creating the character");
+        };
+        assert_eq!(view.draft.attributes.strength, 14);
+        assert_eq!(view.draft.attributes.points_left(), 6);
+        assert!(harness.screen().contains(&format!("Attack roll: {}", view.draft.character().attack())));
+
+        harness.play("Enter").unwrap();
+        let game = &harness.game;
+        assert!(game.phase == GamePhase::PlayingWorld);
+        assert_eq!(game.world.generation_seed, 42);
+        assert_eq!((game.world.width, game.world.height), (128, 64));
+        let character = &game.player.character;
+        assert_eq!((character.name.as_str(), character.species, character.class.as_str()), ("Bramble", Species::Elf, "Rogue"));
+        // The chosen species decides the home town
+        let home = game.world.get_tile(&game.player.world_pos).location.as_ref();
+        assert_eq!(home.map(|location| location.species), Some(Species::Elf));
+    }
+
+    #[test]
+    fn new_worlds_go_to_the_debug_hook() {
+        let mut harness = Harness::new(SEED);
+        let seeds = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let seen = seeds.clone();
+        harness.game.on_new_world = Some(Box::new(move |world| {
+            seen.borrow_mut().push(world.generation_seed);
+            Err("disk full".into())
+        }));
+        harness.game.new_game(7, WORLD_WIDTH, WORLD_HEIGHT, hero("Warrior"));
+        assert_eq!(*seeds.borrow(), vec![7]);
+        // A failed dump is reported, not fatal, and the hook stays for the next game
+        assert_eq!(harness.message(), Some("Could not dump the world: disk full"));
+        assert!(harness.game.on_new_world.is_some());
+    }
+
+    #[test]
+    fn a_character_needs_a_name() {
+        let mut harness = Harness { game: Game::title_screen() };
+        harness.play("Enter Enter Enter").unwrap();
+        assert!(harness.screen().contains("Your character needs a name."));
+        assert!(matches!(harness.game.phase, GamePhase::Menu(_)));
+    }
+
+    #[test]
+    fn hostile_settlements_turn_you_away() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        let town = harness.game.world.tiles[home.y][home.x].location.as_mut().unwrap();
+        town.state = LocationState::Thriving;
+
+        // Humans keep orcs out of their towns
+        harness.game.player.character.species = Species::Orc;
+        harness.command(Command::Enter);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+        assert!(harness.message().is_some_and(|message| message.contains("will not let Orcs")));
+
+        harness.game.player.character.species = Species::Elf;
+        harness.command(Command::Enter);
+        assert_eq!(harness.location_depth(), 1);
+        assert!(harness.message().is_some_and(|message| message.contains("welcome you warmly")));
+    }
+
+    #[test]
+    fn cats_see_further_at_night() {
+        let mut harness = Harness::new(SEED);
+        harness.game.clock.advance_hours(14);
+        assert!(harness.game.clock.is_night());
+        harness.game.update_visibility();
+        let human_sight = harness.game.world.visible.len();
+
+        harness.game.player.character.species = Species::Cat;
+        harness.game.update_visibility();
+        assert!(harness.game.world.visible.len() > human_sight);
+    }
+
+    fn hero(class: &str) -> Character {
+        Character::new("Tester".to_string(), class.to_string(), Species::Human, Attributes::point_buy())
+    }
+
+    fn wolf() -> Enemy {
+        let wolf = monsters_in(TerrainType::Plains).into_iter().find(|monster| monster.name == "Wolf");
+        Enemy::from(wolf.expect("wolves roam the plains"))
+    }
+
+    #[test]
+    fn classes_start_with_their_equipment() {
+        let mut harness = Harness::with_character(SEED, hero("Warrior"));
+        harness.key(KeyCode::Char('i'));
+        assert!(matches!(harness.game.phase, GamePhase::Inventory(_)));
+        let screen = harness.screen();
+        assert!(screen.contains("Weapon     Longsword (Str +3)"));
+        assert!(screen.contains("Body       Chainmail (Dex -1, Con +2)"));
+        assert!(screen.contains(" Rations "));
+
+        // Take the sword off and put it back on from the pack
+        let attack = harness.game.player.character.attack();
+        harness.key(KeyCode::Enter);
+        assert_eq!(harness.message(), Some("You take off the Longsword."));
+        assert_eq!(harness.game.player.character.attack(), attack - 3);
+        for _ in 0..EQUIP_SLOTS.len() + 1 {
+            harness.key(KeyCode::Down);
+        }
+        harness.key(KeyCode::Enter);
+        assert_eq!(harness.message(), Some("You put on the Longsword."));
+        assert_eq!(harness.game.player.character.attack(), attack);
+
+        // The highlight stays on the pack, now down to the rations
+        harness.key(KeyCode::Enter);
+        assert_eq!(harness.message(), Some("The Rations can't be worn."));
+
+        harness.key(KeyCode::Char('i'));
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+    }
+
+    #[test]
+    fn a_cleric_heals_outside_of_combat() {
+        let mut harness = Harness::with_character(SEED, hero("Cleric"));
+        harness.game.player.character.health = 10;
+        harness.key(KeyCode::Char('a'));
+        assert_eq!(harness.game.ability_menu, Some(0));
+        assert!(harness.screen().contains("Abilities - Faith 10/10"));
+
+        // 25 from the prayer and 5 from the cleric's intelligence of 10
+        harness.key(KeyCode::Enter);
+        let character = &harness.game.player.character;
+        assert_eq!(character.spell_power(), 5);
+        assert_eq!(character.health, 40);
+        assert_eq!(character.resource, 6);
+        assert_eq!(harness.message(), Some("Your Heal restores 30 health."));
+
+        // Still cooling down
+        harness.key(KeyCode::Char('a')).key(KeyCode::Enter);
+        assert_eq!(harness.message(), Some("Heal will be ready in 3 turns."));
+        assert_eq!(harness.game.player.character.health, 40);
+    }
+
+    #[test]
+    fn fighting_a_wolf() {
+        let mut harness = Harness::with_character(SEED, hero("Warrior"));
+        harness.game.player.character.attributes.strength = 40;
+        let xp = harness.game.player.character.xp;
+        harness.game.start_combat(wolf());
+        assert!(harness.screen().contains("Fighting a Wolf"));
+        // No saving your way out of it
+        harness.command(Command::Save);
+        assert_eq!(harness.message(), Some("You can't save in the middle of a fight."));
+        assert!(matches!(harness.game.phase, GamePhase::Combat(_)));
+
+        // Power Attack, then plain attacks until it falls
+        harness.command(Command::Move(Direction::South)).command(Command::Confirm);
+        for _ in 0..50 {
+            if !matches!(harness.game.phase, GamePhase::Combat(_)) {
+                break;
+            }
+            harness.command(Command::Move(Direction::North)).command(Command::Confirm);
+        }
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+        assert_eq!(harness.game.player.character.xp, xp + 10);
+        assert_eq!(harness.game.player.character.resource, 6);
+        assert_eq!(harness.message(), Some("You defeat the Wolf and gain 10 XP. Word of it reaches Settlement."));
+    }
+
+    #[test]
+    fn levelling_up_from_a_fight() {
+        let mut harness = Harness::with_character(SEED, hero("Warrior"));
+        let character = &mut harness.game.player.character;
+        character.attributes.strength = 40;
+        character.xp = xp_for_level(2) - 1;
+        let max_health = character.max_health();
+        harness.game.start_combat(wolf());
+        for _ in 0..50 {
+            if !matches!(harness.game.phase, GamePhase::Combat(_)) {
+                break;
+            }
+            harness.command(Command::Confirm);
+        }
+        let character = &harness.game.player.character;
+        assert_eq!(character.level, 2);
+        assert_eq!(character.max_health(), max_health + 12);
+        assert_eq!(
+            harness.message(),
+            Some("You defeat the Wolf and gain 10 XP. Word of it reaches Settlement. You reach level 2! Press [Shift+C] to spend 2 attribute points.")
+        );
+
+        // Spend both points on dexterity from the character sheet
+        harness.key(KeyCode::Char('C'));
+        assert!(matches!(harness.game.phase, GamePhase::CharacterSheet(_)));
+        assert!(harness.screen().contains("Points to spend: 2"));
+        let dodge = harness.game.player.character.dodge();
+        harness.key(KeyCode::Down).key(KeyCode::Enter).key(KeyCode::Enter).key(KeyCode::Enter);
+        assert_eq!(harness.game.player.character.dodge(), dodge + 2);
+        assert_eq!(harness.game.player.character.unspent_points, 0);
+        assert!(harness.screen().contains("You have no attribute points to spend."));
+
+        harness.key(KeyCode::Char('C'));
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+    }
+
+    #[test]
+    fn dying_in_a_fight_ends_the_run() {
+        let mut harness = Harness::with_character(SEED, hero("Mage"));
+        let character = &mut harness.game.player.character;
+        (character.health, character.attributes.strength, character.attributes.dexterity) = (1, 0, -100);
+        harness.game.start_combat(wolf());
+        for _ in 0..50 {
+            if !matches!(harness.game.phase, GamePhase::Combat(_)) {
+                break;
+            }
+            // Swing feebly until the wolf wins
+            harness.command(Command::Confirm);
+        }
+        let GamePhase::GameOver(summary) = &harness.game.phase else {
+            panic!("the wolf should have won");
+        };
+        assert_eq!(summary.cause, "Slain by a Wolf");
+    }
+
+    #[test]
+    fn stealth_keeps_monsters_away() {
+        let mut harness = Harness::with_character(SEED, hero("Rogue"));
+        // Stand somewhere wild, where every step brings trouble
+        let world = &harness.game.world;
+        let wild = (0..world.height)
+            .flat_map(|y| (0..world.width).map(move |x| Position::new(x, y)))
+            .find(|pos| {
+                let tile = world.get_tile(pos);
+                tile.location.is_none() && !monsters_in(tile.terrain).is_empty()
+            })
+            .expect("the world has wilderness");
+        harness.game.player.world_pos = wild;
+        harness.game.encounter_chance = 100;
+
+        harness.key(KeyCode::Char('a')).key(KeyCode::Enter);
+        for _ in 0..20 {
+            harness.game.world_step_taken();
+        }
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+        harness.game.world_step_taken();
+        assert!(matches!(harness.game.phase, GamePhase::Combat(_)));
+
+        // By now Stealth is ready again, and it slips out of fights too
+        harness.commands(&[Command::Move(Direction::South), Command::Confirm]);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+        assert!(harness.message().is_some_and(|message| message.contains("melt into the shadows")));
+    }
+
+    #[test]
+    fn cursed_sites_curse_and_sacred_sites_bless() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        let luck = harness.game.player.character.attribute(Attribute::Luck);
+        let site = harness.game.world.tiles[home.y][home.x].location.as_mut().unwrap();
+        site.state = LocationState::Cursed;
+        harness.command(Command::Enter);
+        assert_eq!(harness.location_depth(), 1);
+        assert!(harness.game.player.character.has_effect(CURSED));
+        assert_eq!(harness.game.player.character.attribute(Attribute::Luck), luck - 3);
+        assert!(harness.screen().contains("Cursed (200 turns)"));
+
+        harness.command(Command::Exit);
+        assert!(harness.screen().contains("[W] Cleanse"));
+        let site = harness.game.world.tiles[home.y][home.x].location.as_mut().unwrap();
+        site.state = LocationState::Sacred;
+        assert_eq!(harness.game.available_interactions(), vec![Interaction::Pray]);
+        assert!(harness.screen().contains("[P] Pray"));
+        harness.command(Command::Interact(Interaction::Pray));
+        let character = &harness.game.player.character;
+        assert!(!character.has_effect(CURSED));
+        assert!(character.has_effect(BLESSED));
+        assert!(harness.message().is_some_and(|message| message.ends_with("Your curse is lifted.")));
+    }
+
+    #[test]
+    fn settlements_change_while_the_player_is_away() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        let away = harness.game.world.find_nearest_location(&home, |pos, _| *pos != home).unwrap();
+        for pos in [home, away] {
+            let site = harness.game.world.tiles[pos.y][pos.x].location.as_mut().unwrap();
+            site.state = LocationState::Struggling;
+            site.size = 5;
+        }
+        let site = harness.game.world.get_tile(&away).location.clone().unwrap();
+        harness.game.journal.record_place(away, &site);
+
+        // Nothing happens until a day has passed
+        harness.game.tick();
+        assert_eq!(harness.game.world.get_tile(&away).location.as_ref().unwrap().state, LocationState::Struggling);
+        harness.game.clock.advance_hours(24);
+        harness.game.tick();
+        let site = harness.game.world.get_tile(&away).location.as_ref().unwrap();
+        assert_eq!(site.state, LocationState::Abandoned);
+        let entry = harness.game.journal.place_at(&away).unwrap();
+        assert_eq!(entry.state, LocationState::Abandoned);
+        assert!(entry.description.starts_with("An abandoned"));
+        // The player's own town waits for them to leave
+        assert_eq!(harness.game.world.get_tile(&home).location.as_ref().unwrap().state, LocationState::Struggling);
+
+        let loaded = reloaded(&harness.game);
+        assert_eq!(loaded.simulated_day, harness.game.simulated_day);
+        let home_site = loaded.world.get_tile(&home).location.as_ref().unwrap();
+        assert_eq!((home_site.state, home_site.size), (LocationState::Struggling, 5));
+        assert_eq!(loaded.world.get_tile(&away).location.as_ref().unwrap().state, LocationState::Abandoned);
+    }
+
+    #[test]
+    fn cleansing_a_cursed_site_leaves_it_to_be_searched() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        let site = harness.game.world.tiles[home.y][home.x].location.as_mut().unwrap();
+        site.state = LocationState::Cursed;
+        harness.command(Command::Interact(Interaction::Search));
+        assert_eq!(harness.message(), Some("There is nothing here to search."));
+
+        // Every failed attempt curses the player; keep at it until the curse breaks
+        let xp = harness.game.player.character.xp;
+        for _ in 0..50 {
+            harness.command(Command::Interact(Interaction::Cleanse));
+            if harness.game.available_interactions() != vec![Interaction::Cleanse] {
+                break;
+            }
+            assert!(harness.game.player.character.has_effect(CURSED));
+        }
+        let site = harness.game.world.get_tile(&home).location.as_ref().unwrap();
+        assert_eq!(site.state, LocationState::Abandoned);
+        assert_eq!(harness.game.journal.place_at(&home).unwrap().state, LocationState::Abandoned);
+        assert!(!harness.game.player.character.has_effect(CURSED));
+        assert!(harness.game.player.character.xp > xp);
+
+        assert_eq!(harness.game.available_interactions(), vec![Interaction::Search]);
+        let before = harness.game.clock.seconds;
+        harness.command(Command::Interact(Interaction::Search));
+        assert!(harness.game.clock.seconds > before);
+        assert!(harness.game.available_interactions().is_empty());
+        assert!(!harness.screen().contains("Search"));
+
+        // What the player did to the place survives a save
+        let loaded = reloaded(&harness.game);
+        let site = loaded.world.get_tile(&home).location.as_ref().unwrap();
+        assert_eq!(site.state, LocationState::Abandoned);
+        assert!(site.searched);
+    }
+
+    #[test]
+    fn resting_at_the_tavern() {
+        let mut harness = Harness::new(SEED);
+        harness.command(Command::Enter);
+        assert!(!harness.game.available_interactions().contains(&Interaction::Rest));
+        harness.command(Command::Interact(Interaction::Rest));
+        assert_eq!(harness.message(), Some("There is nothing here to rest."));
+
+        let tavern = harness.feature_position(FeatureType::Tavern).expect("towns have a tavern");
+        harness.game.player.local_pos = tavern;
+        assert!(harness.screen().contains("[R] Rest"));
+        let character = &mut harness.game.player.character;
+        character.health = 1;
+        character.apply_effect(EXHAUSTED);
+        let before = harness.game.clock.seconds;
+        harness.command(Command::Interact(Interaction::Rest));
+        let character = &harness.game.player.character;
+        assert_eq!(character.health, character.max_health());
+        assert!(!character.has_effect(EXHAUSTED));
+        assert_eq!(harness.game.clock.seconds - before, 8 * 3600);
+        assert_eq!(harness.game.player.gold, STARTING_GOLD - 5);
+    }
+
+    #[test]
+    fn trading_at_the_market() {
+        let mut harness = Harness::new(SEED);
+        harness.command(Command::Enter);
+        let market = harness.feature_position(FeatureType::Market).expect("towns have a market");
+        harness.game.player.local_pos = market;
+        assert!(harness.game.available_interactions().contains(&Interaction::Trade));
+        harness.command(Command::Interact(Interaction::Visit));
+        assert!(matches!(harness.game.phase, GamePhase::Feature(_)));
+        assert!(harness.screen().contains("Trade at the market"));
+        harness.command(Command::Confirm);
+        let GamePhase::Trade(view) = &harness.game.phase else {
+            panic!("the market opens the trade screen");
+        };
+        let market = view.market;
+        let first = market.stock(&view.wares).remove(0);
+        let price = market.price(&first, &harness.game.clock);
+        assert!(harness.screen().contains(&first.name));
+
+        let carried = harness.game.player.character.inventory.len();
+        harness.command(Command::Confirm);
+        assert_eq!(harness.game.player.gold, STARTING_GOLD - price);
+        assert_eq!(harness.game.player.character.inventory.len(), carried + 1);
+        // and the town thinks a little better of its customers
+        let home = harness.game.world.get_tile(&harness.game.player.world_pos).location.as_ref().unwrap();
+        assert!(harness.game.player.reputation.get(Faction::of(home)) > 0);
+
+        harness.game.player.gold = 0;
+        harness.command(Command::Confirm);
+        let refusal = format!("You can't afford that. It costs {price} gold and you have 0.");
+        assert_eq!(harness.message(), Some(refusal.as_str()));
+        assert_eq!(harness.game.player.character.inventory.len(), carried + 1);
+
+        // Selling pays less than buying
+        harness.command(Command::Move(Direction::East));
+        let sold = harness.game.player.character.inventory[0].clone();
+        let offer = market.offer(&sold, &harness.game.clock);
+        harness.command(Command::Confirm);
+        assert_eq!(harness.game.player.gold, offer);
+        assert!(offer < market.price(&sold, &harness.game.clock));
+        assert_eq!(harness.game.player.character.inventory.len(), carried);
+
+        // Leaving goes back through the market to the town
+        harness.command(Command::Cancel);
+        assert!(matches!(harness.game.phase, GamePhase::Feature(_)));
+        harness.command(Command::Cancel);
+        assert_eq!(harness.location_depth(), 1);
+    }
+
+    #[test]
+    fn merchant_towns_have_trading_posts() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        let site = harness.game.world.tiles[home.y][home.x].location.as_mut().unwrap();
+        site.industry = Industry::Trading;
+        harness.command(Command::Enter);
+        let map = harness.location().unwrap();
+        let post = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position::new(x, y)))
+            .find(|pos| map.tiles[pos.y][pos.x].tile_type == LocationTileType::Trading)
+            .expect("merchant towns have trading posts");
+        harness.game.player.local_pos = post;
+        harness.command(Command::Interact(Interaction::Trade));
+        assert!(matches!(harness.game.phase, GamePhase::Trade(_)));
+        assert!(harness.screen().contains("Buy or sell"));
+    }
+
+    #[test]
+    fn hated_visitors_are_turned_away() {
+        let mut harness = Harness::new(SEED);
+        let here = harness.game.player.world_pos;
+        let site = harness.game.world.tiles[here.y][here.x].location.as_mut().unwrap();
+        site.state = LocationState::Thriving;
+        let faction = Faction::of(site);
+        harness.game.player.reputation.change(faction, MIN_STANDING);
+        assert_eq!(harness.game.player.reputation.standing(faction), Standing::Hated);
+        harness.command(Command::Enter);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+        assert!(harness.message().unwrap().ends_with("will not let you through their gates."));
+
+        harness.key(KeyCode::Char('f'));
+        assert!(matches!(harness.game.phase, GamePhase::Reputation(_)));
+        assert!(harness.screen().contains(&format!("{faction}")));
+        assert!(harness.screen().contains("hated"));
+        harness.command(Command::Cancel);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+
+        let loaded = reloaded(&harness.game);
+        assert_eq!(loaded.player.reputation, harness.game.player.reputation);
+    }
+
+    #[test]
+    fn hidden_settlements_show_themselves_to_friends() {
+        let mut harness = Harness::new(SEED);
+        let here = harness.game.player.world_pos;
+        let visitor = harness.game.player.character.species;
+        let strangers = PLAYABLE_SPECIES
+            .into_iter()
+            .find(|species| species.attitude_towards(visitor) == Attitude::Neutral)
+            .expect("some species has no strong feelings about the player's");
+        let site = harness.game.world.tiles[here.y][here.x].location.as_mut().unwrap();
+        site.species = strangers;
+        site.state = LocationState::Hidden;
+        site.revealed = false;
+        let faction = Faction::of(site);
+
+        harness.game.update_visibility();
+        assert!(harness.game.world.get_tile(&here).known_location().is_none());
+        harness.command(Command::Enter);
+        assert!(harness.game.phase == GamePhase::PlayingWorld);
+
+        harness.game.player.reputation.change(faction, 20);
+        harness.game.update_visibility();
+        assert!(harness.game.world.get_tile(&here).known_location().is_some());
+        assert!(harness.message().unwrap().ends_with("trust you enough to show you their hidden settlement."));
+    }
+
+    #[test]
+    fn smiths_trainers_and_taverns() {
+        let mut harness = Harness::new(SEED);
+        let offer = |harness: &Harness, feature, wanted: fn(&Deal) -> bool| {
+            harness
+                .game
+                .feature_offers(feature)
+                .into_iter()
+                .find(|offer| wanted(&offer.deal))
+                .unwrap()
+        };
+        harness.game.player.gold = 1000;
+
+        // Fights wear gear down until the smith mends it
+        let character = &mut harness.game.player.character;
+        character.inventory.push(Item::new("Longsword"));
+        character.equip(character.inventory.len() - 1).unwrap();
+        assert!(character.wear_gear(30).is_empty());
+        let repair = offer(&harness, FeatureType::Blacksmith, |deal| *deal == Deal::Repair);
+        assert!(repair.price > 0);
+        harness.game.make_deal(repair);
+        assert_eq!(harness.game.player.character.gear_damage(), 0);
+        let repair = offer(&harness, FeatureType::Blacksmith, |deal| *deal == Deal::Repair);
+        harness.game.make_deal(repair);
+        assert_eq!(harness.message(), Some("Your gear is in good repair."));
+
+        let strength = harness.game.player.character.attribute(Attribute::Strength);
+        let train = offer(&harness, FeatureType::TrainingGround, |deal| {
+            matches!(deal, Deal::Train { attribute: Attribute::Strength, .. })
+        });
+        harness.game.make_deal(train);
+        assert_eq!(harness.game.player.character.attribute(Attribute::Strength), strength + 1);
+
+        let rumours = offer(&harness, FeatureType::Tavern, |deal| *deal == Deal::Rumours);
+        harness.game.make_deal(rumours);
+        assert_eq!(harness.game.journal.markers.len(), 1);
+        assert!(harness.message().is_some_and(|message| message.ends_with("It is marked on your map.")));
+    }
+
+    #[test]
+    fn long_days_on_the_road_are_exhausting() {
+        let mut harness = Harness::new(SEED);
+        harness.game.clock.advance_hours(20);
+        harness.game.world_step_taken();
+        assert!(harness.game.player.character.has_effect(EXHAUSTED));
+        assert!(harness.message().is_some_and(|message| message.ends_with("You are exhausted. Make camp to rest.")));
+
+        harness.command(Command::Camp);
+        harness.game.world_step_taken();
+        assert!(!harness.game.player.character.has_effect(EXHAUSTED));
+    }
+
+    #[test]
+    fn effects_wear_off_while_resting() {
+        let mut harness = Harness::new(SEED);
+        let character = &mut harness.game.player.character;
+        character.apply_effect(CURSED);
+        character.apply_effect(BLESSED);
+        let curse = character.effects.iter_mut().find(|effect| effect.name == CURSED).unwrap();
+        curse.turns_left = 5;
+        let blessing_left = |harness: &Harness| {
+            let effects = &harness.game.player.character.effects;
+            effects.iter().find(|effect| effect.name == BLESSED).map(|effect| effect.turns_left)
+        };
+        // A night in camp is a turn for every hour of it
+        harness.command(Command::Camp);
+        harness.game.tick();
+        assert!(!harness.game.player.character.has_effect(CURSED));
+        assert_eq!(blessing_left(&harness), Some(120 - 8));
+        assert!(harness.message().is_some_and(|message| message.ends_with("You are no longer cursed.")));
+
+        // The same hours don't count twice
+        harness.game.tick();
+        assert_eq!(blessing_left(&harness), Some(120 - 8));
+    }
+
+    #[test]
+    fn running_out_of_health_ends_the_game() {
+        let mut harness = Harness::new(SEED);
+        harness.game.player.character.health = 0;
+        harness.game.tick();
+        let GamePhase::GameOver(summary) = &harness.game.phase else {
+            panic!("the run should be over");
+        };
+        assert_eq!(summary.cause, "Succumbed to their wounds");
+    }
+
+    #[test]
+    fn auto_travel_reaches_the_nearest_settlement() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        harness.command(Command::TravelToSettlement);
+        assert!(harness.game.is_travelling());
+        assert!(harness.finish_travel());
+        let game = &harness.game;
+        assert_ne!(game.player.world_pos, home);
+        assert!(game.world.get_tile(&game.player.world_pos).location.is_some());
+    }
+
+    #[test]
+    fn auto_travel_finds_a_way_round_when_blocked() {
+        let mut harness = Harness::new(SEED);
+        let home = harness.game.player.world_pos;
+        // Nobody lives out here to catch the eye on the way
+        for row in harness.game.world.tiles.iter_mut() {
+            for tile in row.iter_mut() {
+                tile.location = None;
+            }
+        }
+        let world = &harness.game.world;
+        let destination = *world
+            .visible
+            .iter()
+            .filter(|pos| !world.get_tile(pos).blocked && world.distance(&home, pos) == 3)
+            .min_by_key(|pos| (pos.y, pos.x))
+            .expect("open ground in sight of home");
+        let block = |harness: &mut Harness, pos: Position| harness.game.world.tiles[pos.y][pos.x].blocked = true;
+
+        harness.game.travel_to(destination);
+        let next = harness.game.auto_travel.as_ref().unwrap().path[0];
+        block(&mut harness, next);
+        assert!(harness.finish_travel());
+        assert_eq!(harness.game.player.world_pos, destination);
+
+        // With every way in walled off there is nothing to do but stop
+        harness.game.player.world_pos = home;
+        harness.game.travel_to(destination);
+        let next = harness.game.auto_travel.as_ref().unwrap().path[0];
+        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+            let pos = harness.game.world.neighbour(&destination, dx, dy).unwrap();
+            block(&mut harness, pos);
+        }
+        block(&mut harness, next);
+        harness.game.step_auto_travel();
+        assert!(!harness.game.is_travelling());
+        assert_eq!(harness.message(), Some("Your way is blocked."));
+    }
+}
@@ -1,88 +1,546 @@
 use crate::core::game::{Game, GamePhase};
+use crate::core::map_view::MapView;
+use crate::core::journal_view::{JournalTab, JournalView};
+use crate::core::inventory_view::InventoryRow;
+use crate::core::command::{Command, Direction};
+use crate::systems::character::ATTRIBUTES;
+use crate::core::keymap::{KeyContext, Keymap};
+use crate::core::menu::{CharacterField, KEY_PRESETS, MenuScreen, NewGameField, TitleItem, WORLD_SIZES};
+use crate::core::save::{SAVE_PATH, load_game, save_game};
 use crate::systems::world::World;
 use crate::systems::position::Position;
-use crate::generators::location_generator::{LocationMap, LocationTileType};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crate::systems::clock::local_step_time;
+use crate::systems::combat::Combat;
+use crate::core::trade_view::TradeSide;
+use crate::generators::location_generator::{LocationMap, LocationTileType, NpcRole};
+use crossterm::event::KeyCode;
+
+// Act on a single key press
+pub fn handle_key(game: &mut Game, key: KeyCode) {
+    // While auto-travelling any key stops the journey
+    if game.is_travelling() {
+        game.cancel_travel();
+        game.set_message("You stop travelling.".to_string());
+        return;
+    }
+
+    // While typing a marker note every key goes into the note
+    if let GamePhase::Map(ref view) = game.phase
+        && view.note.is_some()
+    {
+        handle_note_key(game, key);
+        return;
+    }
+
+    // Digits and Backspace edit the seed while its field is highlighted
+    if let GamePhase::Menu(ref mut view) = game.phase
+        && view.screen == MenuScreen::NewGame
+        && view.new_game_field() == NewGameField::Seed
+    {
+        match key {
+            KeyCode::Char(c) if view.type_seed(c) => return,
+            KeyCode::Backspace => {
+                view.erase_seed();
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    // Likewise for the character's name
+    if let GamePhase::Menu(ref mut view) = game.phase
+        && view.screen == MenuScreen::Character
+        && view.character_field() == CharacterField::Name
+    {
+        match key {
+            KeyCode::Char(c) => {
+                view.draft.type_name(c);
+                return;
+            }
+            KeyCode::Backspace => {
+                view.draft.erase_name();
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    let Some(context) = key_context(&game.phase) else {
+        return;
+    };
+    if let Some(command) = game.keymap.command(context, key) {
+        handle_command(game, command);
+    }
+}
+
+// The set of key bindings that applies in a phase
+pub fn key_context(phase: &GamePhase) -> Option<KeyContext> {
+    match phase {
+        GamePhase::PlayingWorld => Some(KeyContext::World),
+        GamePhase::PlayingLocation(_) => Some(KeyContext::Location),
+        GamePhase::Map(_) => Some(KeyContext::Map),
+        GamePhase::Journal(_) => Some(KeyContext::Journal),
+        GamePhase::CharacterSheet(_) => Some(KeyContext::Character),
+        GamePhase::Inventory(_) => Some(KeyContext::Inventory),
+        // Menus and fights only need moving, confirming and cancelling
+        GamePhase::Menu(_) | GamePhase::GameOver(_) | GamePhase::Combat(_) | GamePhase::Feature(_) | GamePhase::Trade(_) | GamePhase::Reputation(_) => {
+            Some(KeyContext::Global)
+        }
+        GamePhase::Exit => None,
+    }
+}
+
+pub fn handle_command(game: &mut Game, command: Command) {
+    // These work on every screen
+    match command {
+        Command::Quit => {
+            if game.is_running() {
+                game.end_run("Retired from adventuring");
+            } else {
+                game.phase = GamePhase::Exit;
+            }
+            return;
+        }
+        // A save doesn't remember the fight, so loading it would escape one
+        Command::Save if matches!(game.phase, GamePhase::Combat(_)) => {
+            game.set_message("You can't save in the middle of a fight.".to_string());
+            return;
+        }
+        Command::Save if game.is_running() => {
+            match save_game(game, SAVE_PATH) {
+                Ok(()) => game.set_message(format!("Game saved to {}.", SAVE_PATH)),
+                Err(e) => game.set_message(format!("Could not save the game: {e}")),
+            }
+            return;
+        }
+        Command::Load => {
+            load_saved_game(game);
+            return;
+        }
+        _ => {}
+    }
+
+    if game.ability_menu.is_some() {
+        handle_ability_menu_command(game, command);
+        return;
+    }
 
-pub fn handle_input(game: &mut Game) -> Result<(), std::io::Error> {
     match game.phase {
-        GamePhase::PlayingWorld => handle_world_input(game),
-        GamePhase::PlayingLocation(_) => handle_location_input(game),
-        GamePhase::Map => handle_map_input(game),
-        _ => Ok(()),
+        GamePhase::PlayingWorld => handle_world_command(game, command),
+        GamePhase::PlayingLocation(_) => handle_location_command(game, command),
+        GamePhase::Map(_) => handle_map_command(game, command),
+        GamePhase::Journal(_) => handle_journal_command(game, command),
+        GamePhase::Combat(_) => handle_combat_command(game, command),
+        GamePhase::CharacterSheet(_) => handle_character_sheet_command(game, command),
+        GamePhase::Inventory(_) => handle_inventory_command(game, command),
+        GamePhase::Feature(_) => handle_feature_command(game, command),
+        GamePhase::Trade(_) => handle_trade_command(game, command),
+        GamePhase::Reputation(_) => handle_reputation_command(game, command),
+        GamePhase::Menu(_) => handle_menu_command(game, command),
+        GamePhase::GameOver(_) => handle_game_over_command(game, command),
+        GamePhase::Exit => {}
     }
+    game.update_visibility();
 }
 
-pub fn handle_world_input(game: &mut Game) -> Result<(), std::io::Error> {
-    match event::read()? {
-        Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-            match key_event.code {
-                KeyCode::Char('q') => {
-                    game.phase = GamePhase::GameOver;
-                }
-                KeyCode::Char('e') => {
-                    let current_tile = game.world.get_tile(&game.player.world_pos);
-                    if current_tile.location.is_some() {
-                        game.enter_location();
+fn load_saved_game(game: &mut Game) {
+    match load_game(SAVE_PATH) {
+        Ok(loaded) => {
+            game.replace_run(loaded);
+            game.update_visibility();
+            game.set_message(format!("Game loaded from {}.", SAVE_PATH));
+        }
+        Err(e) => {
+            let message = format!("Could not load the game: {e}");
+            // The menu has no message box of its own
+            match game.phase {
+                GamePhase::Menu(ref mut view) => view.notice = Some(message),
+                _ => game.set_message(message),
+            }
+        }
+    }
+}
+
+fn handle_menu_command(game: &mut Game, command: Command) {
+    let GamePhase::Menu(ref mut view) = game.phase else {
+        return;
+    };
+    match (view.screen, command) {
+        (_, Command::Move(Direction::North)) => view.move_selection(-1),
+        (_, Command::Move(Direction::South)) => view.move_selection(1),
+        (MenuScreen::Title, Command::Confirm) => match view.title_item() {
+            TitleItem::NewGame => view.open(MenuScreen::NewGame),
+            TitleItem::LoadGame => load_saved_game(game),
+            TitleItem::Settings => view.open(MenuScreen::Settings),
+            TitleItem::Quit => game.phase = GamePhase::Exit,
+        },
+        (MenuScreen::Title, _) => {}
+        (MenuScreen::Character, Command::Cancel) => view.open(MenuScreen::NewGame),
+        (_, Command::Cancel) => view.open(MenuScreen::Title),
+        (MenuScreen::NewGame, Command::Move(direction)) if view.new_game_field() == NewGameField::Size => {
+            match direction {
+                Direction::West => view.cycle_world_size(-1),
+                Direction::East => view.cycle_world_size(1),
+                _ => {}
+            }
+        }
+        (MenuScreen::NewGame, Command::Confirm) => match view.parsed_seed() {
+            Some(_) => view.open(MenuScreen::Character),
+            None => view.notice = Some(format!("The seed must be a number from 0 to {}.", u32::MAX)),
+        },
+        (MenuScreen::Character, Command::Move(direction @ (Direction::West | Direction::East))) => {
+            let delta = if direction == Direction::West { -1 } else { 1 };
+            let field = view.character_field();
+            let draft = &mut view.draft;
+            match field {
+                CharacterField::Species => draft.cycle_species(delta),
+                CharacterField::Class => draft.cycle_class(delta),
+                CharacterField::Method => draft.toggle_method(),
+                CharacterField::Attribute(attribute) if !draft.rolled => {
+                    if delta < 0 {
+                        draft.attributes.lower(attribute);
+                    } else {
+                        draft.attributes.raise(attribute);
                     }
                 }
-                KeyCode::Char('m') => {
-                    game.phase = GamePhase::Map;
-                }
-                KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
-                    handle_player_movement(key_event.code, &mut game.player.world_pos, &game.world);
-                    game.update_interaction_prompt();
-                }
                 _ => {}
             }
         }
+        (MenuScreen::Character, Command::Confirm) => {
+            if view.character_field() == CharacterField::Method && view.draft.rolled {
+                view.draft.reroll();
+            } else if view.draft.name.trim().is_empty() {
+                view.notice = Some("Your character needs a name.".to_string());
+            } else if let Some(seed) = view.parsed_seed() {
+                let (_, width, height) = WORLD_SIZES[view.world_size];
+                let character = view.draft.character();
+                game.new_game(seed, width, height, character);
+            }
+        }
+        (MenuScreen::Settings, Command::Move(direction)) => {
+            match direction {
+                Direction::West => view.cycle_key_preset(-1),
+                Direction::East => view.cycle_key_preset(1),
+                _ => return,
+            }
+            game.keymap = Keymap::preset(KEY_PRESETS[view.key_preset]);
+        }
         _ => {}
     }
-    Ok(())
 }
 
-pub fn handle_map_input(game: &mut Game) -> Result<(), std::io::Error> {
-    match event::read()? {
-        Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-            match key_event.code {
-                KeyCode::Char('m') | KeyCode::Esc => {
-                    game.phase = GamePhase::PlayingWorld;
-                }
-                _ => {}
+fn handle_game_over_command(game: &mut Game, command: Command) {
+    match command {
+        Command::Confirm => {
+            let mut title = Game::title_screen();
+            std::mem::swap(&mut title.keymap, &mut game.keymap);
+            *game = title;
+        }
+        Command::Cancel => game.phase = GamePhase::Exit,
+        _ => {}
+    }
+}
+
+fn handle_world_command(game: &mut Game, command: Command) {
+    match command {
+        Command::Enter => {
+            let current_tile = game.world.get_tile(&game.player.world_pos);
+            if current_tile.known_location().is_some() {
+                game.enter_location();
+            }
+        }
+        Command::OpenMap => {
+            game.phase = GamePhase::Map(MapView::new(game.player.world_pos));
+        }
+        Command::Journal => {
+            game.phase = GamePhase::Journal(JournalView::new());
+        }
+        Command::Survey => game.survey(),
+        Command::TravelToSettlement => game.travel_to_nearest_settlement(),
+        Command::Explore => game.explore(),
+        Command::Camp => game.camp(),
+        Command::Interact(interaction) => game.handle_interaction(interaction),
+        Command::Inventory => game.open_inventory(),
+        Command::Abilities => open_ability_menu(game),
+        Command::CharacterSheet => game.open_character_sheet(),
+        Command::Reputation => game.open_reputation(),
+        Command::Move(direction) => {
+            let moved = handle_player_movement(direction, &mut game.player.world_pos, &game.world);
+            if moved {
+                let terrain = game.world.get_tile(&game.player.world_pos).terrain;
+                game.clock.advance(game.travel_time(terrain, direction.is_diagonal()));
+            }
+            game.update_interaction_prompt();
+            if moved {
+                game.world_step_taken();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn open_ability_menu(game: &mut Game) {
+    if game.player.character.abilities().is_empty() {
+        game.set_message("You have no special abilities.".to_string());
+    } else {
+        game.ability_menu = Some(0);
+    }
+}
+
+fn handle_ability_menu_command(game: &mut Game, command: Command) {
+    let Some(selected) = game.ability_menu else {
+        return;
+    };
+    let count = game.player.character.abilities().len();
+    match command {
+        Command::Move(Direction::North) => game.ability_menu = Some((selected + count - 1) % count),
+        Command::Move(Direction::South) => game.ability_menu = Some((selected + 1) % count),
+        Command::Confirm => {
+            game.ability_menu = None;
+            game.use_ability(selected);
+        }
+        Command::Abilities | Command::Cancel => game.ability_menu = None,
+        _ => {}
+    }
+}
+
+fn handle_combat_command(game: &mut Game, command: Command) {
+    let GamePhase::Combat(ref mut combat) = game.phase else {
+        return;
+    };
+    let actions = Combat::actions(&game.player.character);
+    match command {
+        Command::Move(Direction::North) => combat.move_selection(-1, actions.len()),
+        Command::Move(Direction::South) => combat.move_selection(1, actions.len()),
+        Command::Confirm => {
+            if let Some(&action) = actions.get(combat.selected) {
+                game.combat_action(action);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_note_key(game: &mut Game, key: KeyCode) {
+    let GamePhase::Map(ref mut view) = game.phase else {
+        return;
+    };
+    let Some(note) = view.note.as_mut() else {
+        return;
+    };
+    match key {
+        KeyCode::Char(c) => note.push(c),
+        KeyCode::Backspace => {
+            note.pop();
+        }
+        KeyCode::Enter => {
+            let (cursor, note) = (view.cursor, view.note.take().unwrap_or_default());
+            game.set_marker(cursor, note);
+        }
+        KeyCode::Esc => view.note = None,
+        _ => {}
+    }
+}
+
+fn handle_map_command(game: &mut Game, command: Command) {
+    let GamePhase::Map(ref mut view) = game.phase else {
+        return;
+    };
+    match command {
+        Command::OpenMap | Command::Cancel => {
+            game.phase = GamePhase::PlayingWorld;
+        }
+        Command::ToggleRelief => {
+            game.map_relief = !game.map_relief;
+        }
+        Command::ZoomIn => view.zoom_in(),
+        Command::ZoomOut => view.zoom_out(),
+        Command::CentreMap => view.cursor = game.player.world_pos,
+        Command::AddNote => {
+            // Start from the existing note so it can be edited
+            let note = game.journal.marker_at(&view.cursor).map(|marker| marker.note.clone());
+            view.note = Some(note.unwrap_or_default());
+        }
+        Command::DeleteNote => {
+            let cursor = view.cursor;
+            game.remove_marker(cursor);
+        }
+        Command::Confirm => {
+            let destination = view.cursor;
+            game.phase = GamePhase::PlayingWorld;
+            game.travel_to(destination);
+        }
+        Command::Move(direction) => {
+            let (dx, dy) = direction.delta();
+            view.move_cursor(&game.world, dx, dy);
+        }
+        _ => {}
+    }
+}
+
+fn handle_character_sheet_command(game: &mut Game, command: Command) {
+    let GamePhase::CharacterSheet(ref mut sheet) = game.phase else {
+        return;
+    };
+    match command {
+        Command::Move(Direction::North) => sheet.move_selection(-1),
+        Command::Move(Direction::South) => sheet.move_selection(1),
+        Command::Move(Direction::East) | Command::Confirm => {
+            let attribute = ATTRIBUTES[sheet.selected];
+            if !game.player.character.spend_point(attribute) {
+                game.set_message("You have no attribute points to spend.".to_string());
+            }
+        }
+        Command::CharacterSheet | Command::Cancel => game.close_screen(),
+        _ => {}
+    }
+}
+
+fn handle_reputation_command(game: &mut Game, command: Command) {
+    if matches!(command, Command::Reputation | Command::Cancel) {
+        game.close_screen();
+    }
+}
+
+fn handle_inventory_command(game: &mut Game, command: Command) {
+    let GamePhase::Inventory(ref mut view) = game.phase else {
+        return;
+    };
+    let character = &mut game.player.character;
+    match command {
+        Command::Move(Direction::North) => view.move_selection(-1, character.inventory.len()),
+        Command::Move(Direction::South) => view.move_selection(1, character.inventory.len()),
+        Command::Confirm => {
+            let result = match view.row() {
+                InventoryRow::Worn(slot) => character.unequip(slot),
+                InventoryRow::Pack(index) => character.equip(index),
+            };
+            view.clamp(character.inventory.len());
+            match result {
+                Ok(message) | Err(message) => game.set_message(message),
+            }
+        }
+        Command::Inventory | Command::Cancel => game.close_screen(),
+        _ => {}
+    }
+}
+
+fn handle_feature_command(game: &mut Game, command: Command) {
+    let GamePhase::Feature(ref view) = game.phase else {
+        return;
+    };
+    let offers = game.feature_offers(view.feature.feature_type);
+    let GamePhase::Feature(ref mut view) = game.phase else {
+        return;
+    };
+    match command {
+        Command::Move(Direction::North) => view.move_selection(-1, offers.len()),
+        Command::Move(Direction::South) => view.move_selection(1, offers.len()),
+        Command::Confirm => {
+            if let Some(offer) = offers.into_iter().nth(view.selected) {
+                game.make_deal(offer);
+            }
+        }
+        Command::Cancel => game.close_screen(),
+        _ => {}
+    }
+}
+
+fn handle_trade_command(game: &mut Game, command: Command) {
+    let GamePhase::Trade(ref mut view) = game.phase else {
+        return;
+    };
+    let len = match view.side {
+        TradeSide::Buy => view.market.stock(&view.wares).len(),
+        TradeSide::Sell => game.player.character.inventory.len(),
+    };
+    match command {
+        Command::Move(Direction::North) => view.move_selection(-1, len),
+        Command::Move(Direction::South) => view.move_selection(1, len),
+        Command::Move(Direction::East | Direction::West) => view.switch_side(),
+        Command::Confirm => game.trade(),
+        Command::Cancel => game.close_screen(),
+        _ => {}
+    }
+}
+
+fn handle_journal_command(game: &mut Game, command: Command) {
+    let GamePhase::Journal(ref mut view) = game.phase else {
+        return;
+    };
+    let player_pos = game.player.world_pos;
+    let world = &game.world;
+    let distance = |pos: &Position| world.distance(&player_pos, pos);
+    let positions: Vec<Position> = match view.tab {
+        JournalTab::Places => game.journal.sorted_places(view.sort, distance)
+            .into_iter()
+            .map(|entry| entry.position)
+            .collect(),
+        JournalTab::Markers => game.journal.sorted_markers(view.sort, distance)
+            .into_iter()
+            .map(|marker| marker.position)
+            .collect(),
+    };
+
+    match command {
+        Command::Journal | Command::Cancel => {
+            game.phase = GamePhase::PlayingWorld;
+        }
+        Command::NextTab => view.switch_tab(),
+        Command::CycleSort => view.cycle_sort(),
+        Command::Move(Direction::North) => view.move_selection(-1, positions.len()),
+        Command::Move(Direction::South) => view.move_selection(1, positions.len()),
+        Command::Confirm => {
+            // Show the selected entry on the world map
+            if let Some(&pos) = positions.get(view.selected) {
+                game.phase = GamePhase::Map(MapView::new(pos));
             }
         }
         _ => {}
     }
-    Ok(())
 }
 
-pub fn handle_location_input(game: &mut Game) -> Result<(), std::io::Error> {
-    match event::read()? {
-        Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-            match key_event.code {
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    game.exit_location();
+fn handle_location_command(game: &mut Game, command: Command) {
+    match command {
+        Command::Exit | Command::Cancel => game.exit_location(),
+        Command::StairsUp => game.use_stairs(true),
+        Command::StairsDown => game.use_stairs(false),
+        Command::Enter => {
+            // Enter the feature we are standing on, e.g. a tavern on the street
+            let pos = game.player.local_pos;
+            game.enter_building(pos);
+        }
+        Command::Interact(interaction) => game.handle_interaction(interaction),
+        Command::Inventory => game.open_inventory(),
+        Command::Abilities => open_ability_menu(game),
+        Command::CharacterSheet => game.open_character_sheet(),
+        Command::Reputation => game.open_reputation(),
+        Command::Move(direction) => {
+            if let GamePhase::PlayingLocation(ref stack) = game.phase {
+                let location_map = stack.current();
+                let result = handle_location_movement(direction, &mut game.player.local_pos, location_map);
+                if matches!(result, LocationMove::Moved | LocationMove::Door) {
+                    game.clock.advance(local_step_time(direction.is_diagonal()));
                 }
-                KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
-                    if let GamePhase::PlayingLocation(ref location_map) = game.phase {
-                        handle_location_movement(key_event.code, &mut game.player.local_pos, location_map);
-                    }
+                match result {
+                    LocationMove::Building(pos) => game.enter_building(pos),
+                    LocationMove::Door if stack.depth() > 1 => game.exit_location(),
+                    LocationMove::Npc(role) => game.greet(role),
+                    _ => {}
                 }
-                _ => {}
             }
         }
         _ => {}
     }
-    Ok(())
 }
 
+// Returns whether the player actually moved
 fn handle_player_movement(
-    key: KeyCode,
+    direction: Direction,
     player_pos: &mut Position,
     world: &World,
-) {
-    let (dx, dy) = get_direction(key);
-    if dx == 0 && dy == 0 { return; }
+) -> bool {
+    let (dx, dy) = direction.delta();
 
     let new_x = player_pos.x as i32 + dx;
     let new_y = player_pos.y as i32 + dy;
@@ -92,58 +550,83 @@ fn handle_player_movement(
         let h = world.height() as i32;
         ((new_x + w) % w, (new_y + h) % h)
     } else if !is_in_bounds(new_x, new_y, world.width() as i32, world.height() as i32) {
-        return;
+        return false;
     } else {
         (new_x, new_y)
     };
 
+    // No slipping diagonally between two impassable tiles
+    if direction.is_diagonal() {
+        let is_blocked = |dx, dy| world.neighbour(player_pos, dx, dy).is_none_or(|pos| world.get_tile(&pos).blocked);
+        if is_blocked(dx, 0) && is_blocked(0, dy) {
+            return false;
+        }
+    }
+
     let new_pos = Position { x: new_x as usize, y: new_y as usize };
-    if !world.get_tile(&new_pos).blocked {
-        *player_pos = new_pos;
+    if world.get_tile(&new_pos).blocked {
+        return false;
     }
+    *player_pos = new_pos;
+    true
+}
+
+// What happened when the player tried to move inside a location
+enum LocationMove {
+    Stayed,
+    Moved,
+    // Bumped into an enterable building at this position
+    Building(Position),
+    // Stepped onto the door of the building we are in
+    Door,
+    // Bumped into someone
+    Npc(NpcRole),
 }
 
 fn handle_location_movement(
-    key: KeyCode,
+    direction: Direction,
     player_pos: &mut Position,
     location_map: &LocationMap,
-) {
-    let (dx, dy) = get_direction(key);
-    if dx == 0 && dy == 0 { return; }
+) -> LocationMove {
+    let (dx, dy) = direction.delta();
 
     let new_x = player_pos.x as i32 + dx;
     let new_y = player_pos.y as i32 + dy;
 
     if !is_in_bounds(new_x, new_y, location_map.width as i32, location_map.height as i32) {
-        return;
+        return LocationMove::Stayed;
+    }
+
+    // No slipping diagonally between two walls
+    if direction.is_diagonal() {
+        let is_blocked = |x: i32, y: i32| location_map.tiles[y as usize][x as usize].tile_type.is_blocking();
+        if is_blocked(new_x, player_pos.y as i32) && is_blocked(player_pos.x as i32, new_y) {
+            return LocationMove::Stayed;
+        }
     }
 
     let new_pos = Position { x: new_x as usize, y: new_y as usize };
     let new_tile = &location_map.tiles[new_y as usize][new_x as usize];
-    
-    let blocked = matches!(new_tile.tile_type, 
-        LocationTileType::Wall | 
-        LocationTileType::HumanHouse | 
-        LocationTileType::ElfTreehouse | 
-        LocationTileType::OrcHut
-    );
 
-    if !blocked {
-        *player_pos = new_pos;
+    if new_tile.tile_type.is_building() {
+        return LocationMove::Building(new_pos);
+    }
+    if let Some(npc) = location_map.npc_at(&new_pos) {
+        return LocationMove::Npc(npc.role);
+    }
+    if new_tile.tile_type.is_blocking() {
+        return LocationMove::Stayed;
     }
-}
 
-// Helper functions to reduce duplication
-fn get_direction(key: KeyCode) -> (i32, i32) {
-    match key {
-        KeyCode::Up => (0, -1),
-        KeyCode::Down => (0, 1),
-        KeyCode::Left => (-1, 0),
-        KeyCode::Right => (1, 0),
-        _ => (0, 0),
+    *player_pos = new_pos;
+    if new_tile.tile_type == LocationTileType::Door {
+        LocationMove::Door
+    } else {
+        LocationMove::Moved
     }
 }
 
+// Helper functions to reduce duplication
 fn is_in_bounds(x: i32, y: i32, width: i32, height: i32) -> bool {
     x >= 0 && y >= 0 && x < width && y < height
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use crate::core::keymap::parse_key;
+use crate::prelude::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+
+// Somewhere key presses come from: the terminal, or a script in tests and replays
+pub trait InputSource {
+    // Wait at most `timeout` for the next key press
+    fn poll(&mut self, timeout: Duration) -> io::Result<Option<KeyCode>>;
+
+    // True once the source will never produce another key
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+// Reads key presses from the terminal
+pub struct CrosstermInput;
+
+impl InputSource for CrosstermInput {
+    fn poll(&mut self, timeout: Duration) -> io::Result<Option<KeyCode>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        match event::read()? {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => Ok(Some(key_event.code)),
+            _ => Ok(None),
+        }
+    }
+}
+
+// Plays back a fixed list of key presses, one per poll, without waiting
+pub struct ScriptedInput {
+    keys: VecDeque<KeyCode>,
+}
+
+impl ScriptedInput {
+    pub fn new(keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        Self { keys: keys.into_iter().collect() }
+    }
+
+    // Key names separated by whitespace, as in the key binding config,
+    // e.g. "e Down Down q"
+    pub fn from_script(script: &str) -> Result<Self> {
+        let keys = script
+            .split_whitespace()
+            .map(|name| parse_key(name).ok_or_else(|| format!("unknown key '{name}'")))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self::new(keys))
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, _timeout: Duration) -> io::Result<Option<KeyCode>> {
+        Ok(self.keys.pop_front())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::core::game::GamePhase;
+use crate::systems::equipment::{EQUIP_SLOTS, EquipSlot};
+
+// A row of the inventory screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InventoryRow {
+    Worn(EquipSlot),
+    // Index into the pack
+    Pack(usize),
+}
+
+// State of the inventory screen: the highlighted row, the worn slots first
+// and then the pack, and the screen to go back to when it closes.
+#[derive(PartialEq)]
+pub struct InventoryView {
+    pub selected: usize,
+    pub previous: Box<GamePhase>,
+}
+
+impl InventoryView {
+    pub fn new(previous: GamePhase) -> Self {
+        Self { selected: 0, previous: Box::new(previous) }
+    }
+
+    pub fn row(&self) -> InventoryRow {
+        match EQUIP_SLOTS.get(self.selected) {
+            Some(&slot) => InventoryRow::Worn(slot),
+            None => InventoryRow::Pack(self.selected - EQUIP_SLOTS.len()),
+        }
+    }
+
+    // Move the highlight over the slots and a pack of `pack_len` items
+    pub fn move_selection(&mut self, delta: i32, pack_len: usize) {
+        let rows = (EQUIP_SLOTS.len() + pack_len) as i32;
+        self.selected = (self.selected as i32 + delta).rem_euclid(rows) as usize;
+    }
+
+    // Keep the highlight on the list after the pack shrank
+    pub fn clamp(&mut self, pack_len: usize) {
+        self.selected = self.selected.min(EQUIP_SLOTS.len() + pack_len - 1);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::systems::journal::JournalSort;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JournalTab {
+    Places,
+    Markers,
+}
+
+// State of the journal screen: which list is shown, how it is ordered and
+// which row is highlighted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalView {
+    pub tab: JournalTab,
+    pub sort: JournalSort,
+    pub selected: usize,
+}
+
+impl JournalView {
+    pub fn new() -> Self {
+        Self {
+            tab: JournalTab::Places,
+            sort: JournalSort::Discovered,
+            selected: 0,
+        }
+    }
+
+    pub fn switch_tab(&mut self) {
+        self.tab = match self.tab {
+            JournalTab::Places => JournalTab::Markers,
+            JournalTab::Markers => JournalTab::Places,
+        };
+        self.selected = 0;
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.selected = 0;
+    }
+
+    // Move the highlight, staying within a list of `len` rows
+    pub fn move_selection(&mut self, delta: i32, len: usize) {
+        if len == 0 {
+            self.selected = 0;
+            return;
+        }
+        self.selected = (self.selected as i32 + delta).clamp(0, len as i32 - 1) as usize;
+    }
+}
+
+impl Default for JournalView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,373 @@
+use crate::core::command::{Command, Direction, Interaction};
+use crate::prelude::Result;
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const KEYMAP_PATH: &str = "keys.toml";
+
+// Which screen a binding applies to. Global bindings work everywhere unless a
+// screen binds the same key to something else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Global,
+    World,
+    Location,
+    Map,
+    Journal,
+    Character,
+    Inventory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Default,
+    Vi,
+    Numpad,
+}
+
+// Translates key presses into commands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    // In order of preference when showing which key does what
+    bindings: Vec<(KeyContext, KeyCode, Command)>,
+}
+
+// Layout of the key binding config file: a preset to start from, plus
+// `"key" = "command"` overrides per screen. Use "none" to unbind a key.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct KeymapConfig {
+    preset: Option<Preset>,
+    global: BTreeMap<String, String>,
+    world: BTreeMap<String, String>,
+    location: BTreeMap<String, String>,
+    map: BTreeMap<String, String>,
+    journal: BTreeMap<String, String>,
+    character: BTreeMap<String, String>,
+    inventory: BTreeMap<String, String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(Preset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        use KeyContext::*;
+        let mut keymap = Keymap { bindings: Vec::new() };
+
+        let moves = [
+            (KeyCode::Up, Direction::North),
+            (KeyCode::Down, Direction::South),
+            (KeyCode::Right, Direction::East),
+            (KeyCode::Left, Direction::West),
+            // The keys a numpad sends for its corners when num lock is off
+            (KeyCode::Home, Direction::NorthWest),
+            (KeyCode::PageUp, Direction::NorthEast),
+            (KeyCode::End, Direction::SouthWest),
+            (KeyCode::PageDown, Direction::SouthEast),
+        ];
+        for (key, direction) in moves {
+            keymap.bind(Global, key, Command::Move(direction));
+        }
+        keymap.bind(Global, KeyCode::Char('Q'), Command::Quit);
+        keymap.bind(Global, KeyCode::Esc, Command::Cancel);
+        keymap.bind(Global, KeyCode::Enter, Command::Confirm);
+        keymap.bind(Global, KeyCode::F(5), Command::Save);
+        keymap.bind(Global, KeyCode::F(9), Command::Load);
+
+        keymap.bind(World, KeyCode::Char('e'), Command::Enter);
+        keymap.bind(World, KeyCode::Char('m'), Command::OpenMap);
+        keymap.bind(World, KeyCode::Char('j'), Command::Journal);
+        keymap.bind(World, KeyCode::Char('v'), Command::Survey);
+        keymap.bind(World, KeyCode::Char('t'), Command::TravelToSettlement);
+        keymap.bind(World, KeyCode::Char('x'), Command::Explore);
+        keymap.bind(World, KeyCode::Char('c'), Command::Camp);
+        keymap.bind(World, KeyCode::Char('i'), Command::Inventory);
+        keymap.bind(World, KeyCode::Char('a'), Command::Abilities);
+        keymap.bind(World, KeyCode::Char('C'), Command::CharacterSheet);
+        keymap.bind(World, KeyCode::Char('f'), Command::Reputation);
+        keymap.bind(World, KeyCode::Char('p'), Command::Interact(Interaction::Pray));
+        keymap.bind(World, KeyCode::Char('w'), Command::Interact(Interaction::Cleanse));
+        keymap.bind(World, KeyCode::Char('s'), Command::Interact(Interaction::Search));
+
+        keymap.bind(Location, KeyCode::Char('q'), Command::Exit);
+        keymap.bind(Location, KeyCode::Char('e'), Command::Enter);
+        keymap.bind(Location, KeyCode::Char('<'), Command::StairsUp);
+        keymap.bind(Location, KeyCode::Char('>'), Command::StairsDown);
+        keymap.bind(Location, KeyCode::Char('i'), Command::Inventory);
+        keymap.bind(Location, KeyCode::Char('a'), Command::Abilities);
+        keymap.bind(Location, KeyCode::Char('C'), Command::CharacterSheet);
+        keymap.bind(Location, KeyCode::Char('f'), Command::Reputation);
+        keymap.bind(Location, KeyCode::Char('p'), Command::Interact(Interaction::Pray));
+        keymap.bind(Location, KeyCode::Char('w'), Command::Interact(Interaction::Cleanse));
+        keymap.bind(Location, KeyCode::Char('s'), Command::Interact(Interaction::Search));
+        keymap.bind(Location, KeyCode::Char('r'), Command::Interact(Interaction::Rest));
+        keymap.bind(Location, KeyCode::Char('v'), Command::Interact(Interaction::Visit));
+        keymap.bind(Location, KeyCode::Char('t'), Command::Interact(Interaction::Trade));
+
+        keymap.bind(Map, KeyCode::Char('m'), Command::OpenMap);
+        keymap.bind(Map, KeyCode::Char('t'), Command::Confirm);
+        keymap.bind(Map, KeyCode::Char('+'), Command::ZoomIn);
+        keymap.bind(Map, KeyCode::Char('='), Command::ZoomIn);
+        keymap.bind(Map, KeyCode::Char('-'), Command::ZoomOut);
+        keymap.bind(Map, KeyCode::Char('c'), Command::CentreMap);
+        keymap.bind(Map, KeyCode::Char('r'), Command::ToggleRelief);
+        keymap.bind(Map, KeyCode::Char('n'), Command::AddNote);
+        keymap.bind(Map, KeyCode::Char('d'), Command::DeleteNote);
+
+        keymap.bind(Journal, KeyCode::Char('j'), Command::Journal);
+        keymap.bind(Journal, KeyCode::Tab, Command::NextTab);
+        keymap.bind(Journal, KeyCode::Char('s'), Command::CycleSort);
+
+        keymap.bind(Character, KeyCode::Char('C'), Command::CharacterSheet);
+
+        keymap.bind(Inventory, KeyCode::Char('i'), Command::Inventory);
+
+        match preset {
+            Preset::Default => {}
+            Preset::Vi => {
+                let vi_moves = [
+                    ('k', Direction::North),
+                    ('j', Direction::South),
+                    ('l', Direction::East),
+                    ('h', Direction::West),
+                    ('y', Direction::NorthWest),
+                    ('u', Direction::NorthEast),
+                    ('b', Direction::SouthWest),
+                    ('n', Direction::SouthEast),
+                ];
+                for (key, direction) in vi_moves {
+                    keymap.bind(Global, KeyCode::Char(key), Command::Move(direction));
+                }
+                // 'j' and 'n' now move, so the journal and notes move to 'J' and 'N'
+                for context in [World, Journal] {
+                    keymap.unbind(context, KeyCode::Char('j'));
+                    keymap.bind(context, KeyCode::Char('J'), Command::Journal);
+                }
+                keymap.unbind(Map, KeyCode::Char('n'));
+                keymap.bind(Map, KeyCode::Char('N'), Command::AddNote);
+            }
+            Preset::Numpad => {
+                let numpad_moves = [
+                    ('8', Direction::North),
+                    ('2', Direction::South),
+                    ('6', Direction::East),
+                    ('4', Direction::West),
+                    ('7', Direction::NorthWest),
+                    ('9', Direction::NorthEast),
+                    ('1', Direction::SouthWest),
+                    ('3', Direction::SouthEast),
+                ];
+                for (key, direction) in numpad_moves {
+                    keymap.bind(Global, KeyCode::Char(key), Command::Move(direction));
+                }
+            }
+        }
+        keymap
+    }
+
+    // Load bindings from a config file, falling back to the defaults when
+    // there is no file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::from_config(&text)
+                .map_err(|e| format!("{}: {e}", path.as_ref().display()).into()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn from_config(text: &str) -> Result<Self> {
+        let config: KeymapConfig = toml::from_str(text)?;
+        let mut keymap = Self::preset(config.preset.unwrap_or(Preset::Default));
+
+        let sections = [
+            (KeyContext::Global, &config.global),
+            (KeyContext::World, &config.world),
+            (KeyContext::Location, &config.location),
+            (KeyContext::Map, &config.map),
+            (KeyContext::Journal, &config.journal),
+            (KeyContext::Character, &config.character),
+            (KeyContext::Inventory, &config.inventory),
+        ];
+        for (context, bindings) in sections {
+            for (key_name, command_name) in bindings {
+                let key = parse_key(key_name).ok_or_else(|| format!("unknown key '{key_name}'"))?;
+                if command_name == "none" {
+                    keymap.unbind(context, key);
+                    continue;
+                }
+                let command = Command::from_name(command_name)
+                    .ok_or_else(|| format!("unknown command '{command_name}'"))?;
+                keymap.bind(context, key, command);
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn bind(&mut self, context: KeyContext, key: KeyCode, command: Command) {
+        match self.bindings.iter_mut().find(|(c, k, _)| *c == context && *k == key) {
+            Some(binding) => binding.2 = command,
+            None => self.bindings.push((context, key, command)),
+        }
+    }
+
+    pub fn unbind(&mut self, context: KeyContext, key: KeyCode) {
+        self.bindings.retain(|(c, k, _)| !(*c == context && *k == key));
+    }
+
+    // The command `key` stands for on a screen, if any
+    pub fn command(&self, context: KeyContext, key: KeyCode) -> Option<Command> {
+        let lookup = |wanted: KeyContext| {
+            self.bindings
+                .iter()
+                .find(|(c, k, _)| *c == wanted && *k == key)
+                .map(|(_, _, command)| *command)
+        };
+        lookup(context).or_else(|| lookup(KeyContext::Global))
+    }
+
+    // Name of the key that triggers `command` on a screen, for help text
+    pub fn key_label(&self, context: KeyContext, command: Command) -> String {
+        [context, KeyContext::Global]
+            .iter()
+            .find_map(|&wanted| {
+                self.bindings
+                    .iter()
+                    .find(|(c, k, bound)| {
+                        *c == wanted && *bound == command && self.command(context, *k) == Some(command)
+                    })
+                    .map(|(_, key, _)| key_name(*key))
+            })
+            .unwrap_or_else(|| "unbound".to_string())
+    }
+}
+
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    match name.to_lowercase().as_str() {
+        "up" => Some(KeyCode::Up),
+        "down" => Some(KeyCode::Down),
+        "left" => Some(KeyCode::Left),
+        "right" => Some(KeyCode::Right),
+        "enter" => Some(KeyCode::Enter),
+        "esc" => Some(KeyCode::Esc),
+        "tab" => Some(KeyCode::Tab),
+        "backspace" => Some(KeyCode::Backspace),
+        "home" => Some(KeyCode::Home),
+        "end" => Some(KeyCode::End),
+        "pageup" => Some(KeyCode::PageUp),
+        "pagedown" => Some(KeyCode::PageDown),
+        "space" => Some(KeyCode::Char(' ')),
+        other => other
+            .strip_prefix('f')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (1..=12).contains(n))
+            .map(KeyCode::F),
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) if c.is_ascii_uppercase() => format!("Shift+{c}"),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXTS: [KeyContext; 7] = [
+        KeyContext::Global,
+        KeyContext::World,
+        KeyContext::Location,
+        KeyContext::Map,
+        KeyContext::Journal,
+        KeyContext::Character,
+        KeyContext::Inventory,
+    ];
+
+    #[test]
+    fn key_names() {
+        assert_eq!(parse_key("j"), Some(KeyCode::Char('j')));
+        assert_eq!(parse_key("J"), Some(KeyCode::Char('J')));
+        assert_eq!(parse_key("PageUp"), Some(KeyCode::PageUp));
+        assert_eq!(parse_key("space"), Some(KeyCode::Char(' ')));
+        assert_eq!(parse_key("F12"), Some(KeyCode::F(12)));
+        assert_eq!(parse_key("f0"), None);
+        assert_eq!(parse_key("F13"), None);
+        assert_eq!(parse_key("shift"), None);
+        assert_eq!(parse_key(""), None);
+    }
+
+    #[test]
+    fn config_overrides_and_unbinds() {
+        let keymap = Keymap::from_config("preset = \"vi\"\n[world]\n\"g\" = \"journal\"\n\"c\" = \"none\"\n").unwrap();
+        assert_eq!(keymap.command(KeyContext::World, KeyCode::Char('g')), Some(Command::Journal));
+        assert_eq!(keymap.command(KeyContext::World, KeyCode::Char('c')), None);
+        // Other screens keep the preset
+        assert_eq!(keymap.command(KeyContext::Location, KeyCode::Char('h')), Some(Command::Move(Direction::West)));
+        assert_eq!(keymap.key_label(KeyContext::World, Command::Camp), "unbound");
+    }
+
+    #[test]
+    fn bad_configs_say_what_is_wrong() {
+        let error = |text| Keymap::from_config(text).err().unwrap().to_string();
+        assert_eq!(error("[world]\n\"shift\" = \"journal\"\n"), "unknown key 'shift'");
+        assert_eq!(error("[world]\n\"g\" = \"dance\"\n"), "unknown command 'dance'");
+        assert!(error("[kitchen]\n\"g\" = \"journal\"\n").contains("kitchen"));
+        assert!(error("preset = \"emacs\"\n").contains("emacs"));
+    }
+
+    #[test]
+    fn no_preset_hides_movement_or_the_way_out() {
+        for preset in [Preset::Default, Preset::Vi, Preset::Numpad] {
+            let keymap = Keymap::preset(preset);
+            let moves: Vec<(KeyCode, Command)> = keymap
+                .bindings
+                .iter()
+                .filter(|(context, _, command)| *context == KeyContext::Global && matches!(command, Command::Move(_)))
+                .map(|(_, key, command)| (*key, *command))
+                .collect();
+            for context in CONTEXTS {
+                for &(key, command) in &moves {
+                    assert_eq!(keymap.command(context, key), Some(command), "{preset:?} {context:?} {key:?}");
+                }
+                for command in [Command::Cancel, Command::Confirm, Command::Quit] {
+                    assert_ne!(keymap.key_label(context, command), "unbound", "{preset:?} {context:?}");
+                }
+            }
+        }
+        // Vi keys take 'j' for moving, so the journal moves to 'J'
+        let vi = Keymap::preset(Preset::Vi);
+        assert_eq!(vi.key_label(KeyContext::World, Command::Journal), "Shift+J");
+        assert_eq!(vi.key_label(KeyContext::Map, Command::AddNote), "Shift+N");
+    }
+}
@@ -0,0 +1,95 @@
+use crate::systems::position::Position;
+use crate::systems::world::World;
+
+// World tiles per map cell at each zoom level
+pub const ZOOM_LEVELS: [usize; 4] = [1, 2, 4, 8];
+const DEFAULT_ZOOM: usize = 1;
+
+// State of the interactive world map: where the cursor is and how far we
+// are zoomed out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapView {
+    pub cursor: Position,
+    zoom: usize,
+    // Text of a marker note being typed for the cursor tile
+    pub note: Option<String>,
+}
+
+impl MapView {
+    pub fn new(cursor: Position) -> Self {
+        Self { cursor, zoom: DEFAULT_ZOOM, note: None }
+    }
+
+    // How many world tiles one map cell covers in each direction
+    pub fn scale(&self) -> usize {
+        ZOOM_LEVELS[self.zoom]
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom + 1).min(ZOOM_LEVELS.len() - 1);
+    }
+
+    // Move the cursor one map cell, which is `scale` tiles when zoomed out
+    pub fn move_cursor(&mut self, world: &World, dx: i32, dy: i32) {
+        let scale = self.scale() as i32;
+        if let Some(pos) = world.neighbour(&self.cursor, dx * scale, dy * scale) {
+            self.cursor = pos;
+        }
+    }
+
+    // Top-left world tile of the cell `(col, row)` cells away from the cursor's cell
+    pub fn cell_origin(&self, world: &World, col: i32, row: i32) -> Option<Position> {
+        let scale = self.scale();
+        let cursor_cell = Position {
+            x: self.cursor.x / scale * scale,
+            y: self.cursor.y / scale * scale,
+        };
+        world.neighbour(&cursor_cell, col * scale as i32, row * scale as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_stays_within_its_levels() {
+        let mut view = MapView::new(Position::new(0, 0));
+        assert_eq!(view.scale(), ZOOM_LEVELS[DEFAULT_ZOOM]);
+        for _ in 0..10 {
+            view.zoom_out();
+        }
+        assert_eq!(view.scale(), ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+        for _ in 0..10 {
+            view.zoom_in();
+        }
+        assert_eq!(view.scale(), ZOOM_LEVELS[0]);
+    }
+
+    #[test]
+    fn the_cursor_moves_a_cell_at_a_time_and_wraps() {
+        let world = World::new(1, 32, 32);
+        let mut view = MapView::new(Position::new(1, 9));
+        view.zoom_out();
+        view.move_cursor(&world, 1, 0);
+        assert_eq!(view.cursor, Position::new(5, 9));
+        view.move_cursor(&world, -1, -1);
+        view.move_cursor(&world, -1, 0);
+        assert_eq!(view.cursor, Position::new(29, 5));
+    }
+
+    #[test]
+    fn cells_line_up_on_the_zoom_grid() {
+        let world = World::new(1, 32, 32);
+        let mut view = MapView::new(Position::new(13, 6));
+        view.zoom_out();
+        assert_eq!(view.cell_origin(&world, 0, 0), Some(Position::new(12, 4)));
+        assert_eq!(view.cell_origin(&world, 1, -1), Some(Position::new(16, 0)));
+        // Across the seam on a wrapping world
+        assert_eq!(view.cell_origin(&world, 0, -2), Some(Position::new(12, 28)));
+    }
+}
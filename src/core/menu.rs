@@ -0,0 +1,248 @@
+use crate::core::keymap::Preset;
+use crate::systems::character::{Attribute, Attributes, Character};
+use crate::systems::class::{ClassDef, classes};
+use crate::systems::location::{PLAYABLE_SPECIES, Species};
+
+// World sizes offered when starting a new game
+pub const WORLD_SIZES: [(&str, usize, usize); 3] = [
+    ("Small", 128, 64),
+    ("Medium", 256, 128),
+    ("Large", 512, 256),
+];
+const DEFAULT_WORLD_SIZE: usize = 2;
+
+// Seeds are typed in as decimal digits
+const MAX_SEED_DIGITS: usize = 10;
+const MAX_NAME_LENGTH: usize = 20;
+
+pub const KEY_PRESETS: [Preset; 3] = [Preset::Default, Preset::Vi, Preset::Numpad];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuScreen {
+    Title,
+    NewGame,
+    Character,
+    Settings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TitleItem {
+    NewGame,
+    LoadGame,
+    Settings,
+    Quit,
+}
+
+pub const TITLE_ITEMS: [TitleItem; 4] = [
+    TitleItem::NewGame,
+    TitleItem::LoadGame,
+    TitleItem::Settings,
+    TitleItem::Quit,
+];
+
+impl TitleItem {
+    pub fn label(&self) -> &str {
+        match self {
+            TitleItem::NewGame => "New Game",
+            TitleItem::LoadGame => "Load Game",
+            TitleItem::Settings => "Settings",
+            TitleItem::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NewGameField {
+    Seed,
+    Size,
+    Start,
+}
+
+pub const NEW_GAME_FIELDS: [NewGameField; 3] = [NewGameField::Seed, NewGameField::Size, NewGameField::Start];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharacterField {
+    Name,
+    Species,
+    Class,
+    // Point-buy or rolled attributes
+    Method,
+    Attribute(Attribute),
+    Begin,
+}
+
+pub const CHARACTER_FIELDS: [CharacterField; 10] = [
+    CharacterField::Name,
+    CharacterField::Species,
+    CharacterField::Class,
+    CharacterField::Method,
+    CharacterField::Attribute(Attribute::Strength),
+    CharacterField::Attribute(Attribute::Dexterity),
+    CharacterField::Attribute(Attribute::Intelligence),
+    CharacterField::Attribute(Attribute::Constitution),
+    CharacterField::Attribute(Attribute::Luck),
+    CharacterField::Begin,
+];
+
+// The character being put together on the creation screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterDraft {
+    pub name: String,
+    // Indices into PLAYABLE_SPECIES and the class list
+    pub species: usize,
+    pub class: usize,
+    pub rolled: bool,
+    pub attributes: Attributes,
+}
+
+impl CharacterDraft {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            species: 0,
+            class: 0,
+            rolled: false,
+            attributes: Attributes::point_buy(),
+        }
+    }
+
+    pub fn type_name(&mut self, c: char) {
+        if !c.is_control() && self.name.chars().count() < MAX_NAME_LENGTH {
+            self.name.push(c);
+        }
+    }
+
+    pub fn erase_name(&mut self) {
+        self.name.pop();
+    }
+
+    pub fn species(&self) -> Species {
+        PLAYABLE_SPECIES[self.species]
+    }
+
+    pub fn class(&self) -> &'static ClassDef {
+        &classes()[self.class]
+    }
+
+    pub fn cycle_species(&mut self, delta: i32) {
+        self.species = (self.species as i32 + delta).rem_euclid(PLAYABLE_SPECIES.len() as i32) as usize;
+    }
+
+    pub fn cycle_class(&mut self, delta: i32) {
+        self.class = (self.class as i32 + delta).rem_euclid(classes().len() as i32) as usize;
+    }
+
+    // Switch between point-buy and rolling; either way starts afresh
+    pub fn toggle_method(&mut self) {
+        self.rolled = !self.rolled;
+        if self.rolled {
+            self.reroll();
+        } else {
+            self.attributes = Attributes::point_buy();
+        }
+    }
+
+    pub fn reroll(&mut self) {
+        self.attributes = Attributes::roll(&mut rand::thread_rng());
+    }
+
+    pub fn character(&self) -> Character {
+        let name = self.name.trim().to_string();
+        Character::new(name, self.class().name.clone(), self.species(), self.attributes)
+    }
+}
+
+impl Default for CharacterDraft {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// State of the title menu and its sub-screens
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuView {
+    pub screen: MenuScreen,
+    // Highlighted row on the current screen
+    pub selected: usize,
+    pub seed: String,
+    pub world_size: usize,
+    pub key_preset: usize,
+    pub draft: CharacterDraft,
+    // Shown under the menu, e.g. when loading failed
+    pub notice: Option<String>,
+}
+
+impl MenuView {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            screen: MenuScreen::Title,
+            selected: 0,
+            seed: seed.to_string(),
+            world_size: DEFAULT_WORLD_SIZE,
+            key_preset: 0,
+            draft: CharacterDraft::new(),
+            notice: None,
+        }
+    }
+
+    pub fn open(&mut self, screen: MenuScreen) {
+        self.screen = screen;
+        self.selected = 0;
+        self.notice = None;
+    }
+
+    fn row_count(&self) -> usize {
+        match self.screen {
+            MenuScreen::Title => TITLE_ITEMS.len(),
+            MenuScreen::NewGame => NEW_GAME_FIELDS.len(),
+            MenuScreen::Character => CHARACTER_FIELDS.len(),
+            MenuScreen::Settings => 1,
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        let rows = self.row_count() as i32;
+        self.selected = (self.selected as i32 + delta).rem_euclid(rows) as usize;
+    }
+
+    pub fn title_item(&self) -> TitleItem {
+        TITLE_ITEMS[self.selected]
+    }
+
+    pub fn new_game_field(&self) -> NewGameField {
+        NEW_GAME_FIELDS[self.selected]
+    }
+
+    pub fn character_field(&self) -> CharacterField {
+        CHARACTER_FIELDS[self.selected]
+    }
+
+    // Edit the seed while its field is highlighted. Returns false for keys
+    // that aren't part of a seed.
+    pub fn type_seed(&mut self, c: char) -> bool {
+        if !c.is_ascii_digit() {
+            return false;
+        }
+        if self.seed.len() < MAX_SEED_DIGITS {
+            self.seed.push(c);
+        }
+        true
+    }
+
+    pub fn erase_seed(&mut self) {
+        self.seed.pop();
+    }
+
+    // The typed seed, or None when it is empty or too big
+    pub fn parsed_seed(&self) -> Option<u32> {
+        self.seed.parse().ok()
+    }
+
+    pub fn cycle_world_size(&mut self, delta: i32) {
+        self.world_size = (self.world_size as i32 + delta).rem_euclid(WORLD_SIZES.len() as i32) as usize;
+    }
+
+    pub fn cycle_key_preset(&mut self, delta: i32) {
+        self.key_preset = (self.key_preset as i32 + delta).rem_euclid(KEY_PRESETS.len() as i32) as usize;
+    }
+}
@@ -1,2 +1,17 @@
 pub mod game;
-pub mod input;
\ No newline at end of file
+pub mod input;
+pub mod input_source;
+pub mod game_loop;
+pub mod command;
+pub mod keymap;
+pub mod map_view;
+pub mod journal_view;
+pub mod character_sheet;
+pub mod inventory_view;
+pub mod feature_view;
+pub mod trade_view;
+pub mod reputation_view;
+pub mod menu;
+pub mod save;
+#[cfg(test)]
+pub mod harness;
@@ -0,0 +1,14 @@
+use crate::core::game::GamePhase;
+
+// State of the reputation screen: just the screen to go back to when it
+// closes, as the standings themselves live on the player.
+#[derive(PartialEq)]
+pub struct ReputationView {
+    pub previous: Box<GamePhase>,
+}
+
+impl ReputationView {
+    pub fn new(previous: GamePhase) -> Self {
+        Self { previous: Box::new(previous) }
+    }
+}
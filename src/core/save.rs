@@ -0,0 +1,234 @@
+use crate::core::game::Game;
+use crate::prelude::Result;
+use crate::systems::character::Character;
+use crate::systems::clock::GameClock;
+use crate::systems::journal::Journal;
+use crate::systems::location::LocationState;
+use crate::systems::player::Player;
+use crate::systems::position::Position;
+use crate::systems::reputation::Reputation;
+use crate::systems::world::World;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const SAVE_PATH: &str = "savegame.toml";
+
+// Bump when the layout of the save file changes. Saves from other versions
+// are refused rather than migrated, so every field is required.
+const SAVE_VERSION: u32 = 4;
+
+// Read on its own first, so a save from another version is turned away
+// with a clear error before the rest of it fails to parse
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+// The world itself is rebuilt from its seed, so a save only has to remember
+// what the player has done to it.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    world: WorldSave,
+    player: PlayerSave,
+    journal: Journal,
+    clock: GameClock,
+    // When the player last woke, in clock seconds
+    awake_since: u64,
+    // The day the world's settlements have been simulated up to
+    simulated_day: u64,
+    // The clock seconds status effects have run up to
+    effects_time: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WorldSave {
+    seed: u32,
+    width: usize,
+    height: usize,
+    // Explored tiles, one bit per tile in row order, as hex
+    seen: String,
+    // How the player left each settlement
+    sites: Vec<SiteSave>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SiteSave {
+    position: Position,
+    state: LocationState,
+    searched: bool,
+    size: usize,
+    prosperity: i32,
+    revealed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlayerSave {
+    character: Character,
+    world_pos: Position,
+    gold: i32,
+    reputation: Reputation,
+}
+
+pub fn save_game(game: &Game, path: impl AsRef<Path>) -> Result<()> {
+    fs::write(path, save_to_string(game)?)?;
+    Ok(())
+}
+
+pub fn load_game(path: impl AsRef<Path>) -> Result<Game> {
+    load_from_str(&fs::read_to_string(path)?)
+}
+
+pub fn save_to_string(game: &Game) -> Result<String> {
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        world: WorldSave {
+            seed: game.world.generation_seed,
+            width: game.world.width,
+            height: game.world.height,
+            seen: encode_seen(&game.world),
+            sites: save_sites(&game.world),
+        },
+        player: PlayerSave {
+            character: game.player.character.clone(),
+            world_pos: game.player.world_pos,
+            gold: game.player.gold,
+            reputation: game.player.reputation.clone(),
+        },
+        journal: game.journal.clone(),
+        clock: game.clock,
+        awake_since: game.awake_since,
+        simulated_day: game.simulated_day,
+        effects_time: game.effects_time,
+    };
+    Ok(toml::to_string(&save)?)
+}
+
+pub fn load_from_str(text: &str) -> Result<Game> {
+    let header: SaveHeader = toml::from_str(text)?;
+    if header.version != SAVE_VERSION {
+        return Err(format!("unsupported save version {}", header.version).into());
+    }
+    let save: SaveFile = toml::from_str(text)?;
+
+    let mut world = World::new(save.world.seed, save.world.width, save.world.height);
+    decode_seen(&mut world, &save.world.seen)?;
+    restore_sites(&mut world, &save.world.sites);
+
+    let pos = save.player.world_pos;
+    if pos.x >= world.width || pos.y >= world.height {
+        return Err("player position is outside the world".into());
+    }
+    let mut player = Player::new(save.player.character, pos.x, pos.y);
+    player.gold = save.player.gold;
+    player.reputation = save.player.reputation;
+
+    let mut game = Game::new(player, world);
+    game.journal = save.journal;
+    game.clock = save.clock;
+    game.awake_since = save.awake_since;
+    game.simulated_day = save.simulated_day;
+    game.effects_time = save.effects_time;
+    Ok(game)
+}
+
+fn save_sites(world: &World) -> Vec<SiteSave> {
+    let mut sites = Vec::new();
+    for (y, row) in world.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if let Some(location) = &tile.location {
+                sites.push(SiteSave {
+                    position: Position::new(x, y),
+                    state: location.state,
+                    searched: location.searched,
+                    size: location.size,
+                    prosperity: location.prosperity,
+                    revealed: location.revealed,
+                });
+            }
+        }
+    }
+    sites
+}
+
+fn restore_sites(world: &mut World, sites: &[SiteSave]) {
+    for site in sites {
+        let location = world
+            .tiles
+            .get_mut(site.position.y)
+            .and_then(|row| row.get_mut(site.position.x))
+            .and_then(|tile| tile.location.as_mut());
+        if let Some(location) = location {
+            location.state = site.state;
+            location.searched = site.searched;
+            location.size = site.size;
+            location.prosperity = site.prosperity;
+            location.revealed = site.revealed;
+        }
+    }
+}
+
+fn encode_seen(world: &World) -> String {
+    let bits: Vec<bool> = world.tiles.iter().flatten().map(|tile| tile.seen).collect();
+    bits.chunks(4)
+        .map(|nibble| {
+            let value = nibble.iter().enumerate().fold(0, |acc, (i, &bit)| acc | ((bit as u32) << i));
+            char::from_digit(value, 16).expect("a nibble is a single hex digit")
+        })
+        .collect()
+}
+
+fn decode_seen(world: &mut World, hex: &str) -> Result<()> {
+    let width = world.width;
+    let expected = (world.width * world.height).div_ceil(4);
+    if hex.len() != expected {
+        return Err("explored tiles don't match the world size".into());
+    }
+    for (i, digit) in hex.chars().enumerate() {
+        let value = digit.to_digit(16).ok_or("explored tiles are not valid hex")?;
+        for bit in 0..4 {
+            let index = i * 4 + bit;
+            if let Some(tile) = world.tiles.get_mut(index / width).and_then(|row| row.get_mut(index % width)) {
+                tile.seen = value & (1 << bit) != 0;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explored_tiles_survive_the_hex_round_trip() {
+        // 7 x 5 tiles doesn't divide into whole hex digits
+        let mut world = World::new(1, 7, 5);
+        for (i, tile) in world.tiles.iter_mut().flatten().enumerate() {
+            tile.seen = i % 3 == 0 || i == 34;
+        }
+        let hex = encode_seen(&world);
+        assert_eq!(hex.len(), 9);
+
+        let mut loaded = World::new(1, 7, 5);
+        decode_seen(&mut loaded, &hex).unwrap();
+        let seen = |world: &World| world.tiles.iter().flatten().map(|tile| tile.seen).collect::<Vec<_>>();
+        assert_eq!(seen(&loaded), seen(&world));
+    }
+
+    #[test]
+    fn bad_explored_tiles_are_rejected() {
+        let mut world = World::new(1, 7, 5);
+        assert!(decode_seen(&mut world, "00").is_err());
+        assert!(decode_seen(&mut world, "00000000g").is_err());
+        assert!(decode_seen(&mut world, "fffffffff").is_ok());
+        assert!(world.tiles.iter().flatten().all(|tile| tile.seen));
+    }
+
+    #[test]
+    fn other_versions_are_turned_away_before_parsing() {
+        let error = load_from_str("version = 0\nsomething = \"else entirely\"\n").err().unwrap();
+        assert_eq!(error.to_string(), "unsupported save version 0");
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::game::GamePhase;
+use crate::systems::economy::Market;
+
+// Which list the trade screen shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+// State of the trade screen: the settlement's market, what it sells besides
+// its own goods, which list is shown with its highlighted row, and the
+// screen to go back to when it closes.
+#[derive(PartialEq)]
+pub struct TradeView {
+    pub name: String,
+    pub market: Market,
+    pub wares: Vec<String>,
+    pub side: TradeSide,
+    pub selected: usize,
+    pub previous: Box<GamePhase>,
+}
+
+impl TradeView {
+    pub fn new(name: String, market: Market, wares: Vec<String>, previous: GamePhase) -> Self {
+        Self { name, market, wares, side: TradeSide::Buy, selected: 0, previous: Box::new(previous) }
+    }
+
+    pub fn switch_side(&mut self) {
+        self.side = match self.side {
+            TradeSide::Buy => TradeSide::Sell,
+            TradeSide::Sell => TradeSide::Buy,
+        };
+        self.selected = 0;
+    }
+
+    // Move the highlight over a list of `len` rows
+    pub fn move_selection(&mut self, delta: i32, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected as i32 + delta).rem_euclid(len as i32) as usize;
+        }
+    }
+
+    // Keep the highlight on the list after it shrinks to `len` rows
+    pub fn clamp(&mut self, len: usize) {
+        self.selected = self.selected.min(len.saturating_sub(1));
+    }
+}
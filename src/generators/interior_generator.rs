@@ -0,0 +1,372 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use crate::generators::location_generator::{
+    FeatureType, LocationMap, LocationTile, LocationTileType, NpcRole, NpcSpot,
+};
+use crate::generators::level_generator::{LevelGenerator, LevelKind};
+use crate::systems::position::Position;
+use std::collections::HashSet;
+
+// The kinds of buildings that can be entered from a location map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuildingKind {
+    House,
+    Treehouse,
+    Hut,
+    Tavern,
+    Temple,
+    Market,
+    Blacksmith,
+    Storage,
+}
+
+impl BuildingKind {
+    // Features take priority over the tile they stand on, so a Blacksmith
+    // placed on a house tile opens into a smithy rather than a plain house.
+    pub fn for_tile(tile: &LocationTile) -> Option<BuildingKind> {
+        tile.feature
+            .as_ref()
+            .and_then(|feature| Self::from_feature(feature.feature_type))
+            .or_else(|| Self::from_tile_type(&tile.tile_type))
+    }
+
+    pub fn from_feature(feature_type: FeatureType) -> Option<BuildingKind> {
+        match feature_type {
+            FeatureType::Market => Some(BuildingKind::Market),
+            FeatureType::Temple => Some(BuildingKind::Temple),
+            FeatureType::Tavern => Some(BuildingKind::Tavern),
+            FeatureType::Blacksmith => Some(BuildingKind::Blacksmith),
+            FeatureType::Storage => Some(BuildingKind::Storage),
+            // Open-air features have no interior
+            FeatureType::Garden | FeatureType::TrainingGround => None,
+        }
+    }
+
+    pub fn from_tile_type(tile_type: &LocationTileType) -> Option<BuildingKind> {
+        match tile_type {
+            LocationTileType::HumanHouse => Some(BuildingKind::House),
+            LocationTileType::ElfTreehouse => Some(BuildingKind::Treehouse),
+            LocationTileType::OrcHut => Some(BuildingKind::Hut),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            BuildingKind::House => "house",
+            BuildingKind::Treehouse => "treehouse",
+            BuildingKind::Hut => "hut",
+            BuildingKind::Tavern => "tavern",
+            BuildingKind::Temple => "temple",
+            BuildingKind::Market => "market hall",
+            BuildingKind::Blacksmith => "smithy",
+            BuildingKind::Storage => "storehouse",
+        }
+    }
+}
+
+pub struct InteriorGenerator {
+    rng: StdRng,
+    kind: BuildingKind,
+}
+
+impl InteriorGenerator {
+    pub fn new(seed: u64, kind: BuildingKind) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            kind,
+        }
+    }
+
+    pub fn generate(&mut self) -> LocationMap {
+        let (width, height) = self.determine_size();
+        let mut map = self.create_walled_room(width, height);
+
+        match self.kind {
+            BuildingKind::House | BuildingKind::Treehouse => self.furnish_house(&mut map),
+            BuildingKind::Hut => self.furnish_hut(&mut map),
+            BuildingKind::Tavern => self.furnish_tavern(&mut map),
+            BuildingKind::Temple => self.furnish_temple(&mut map),
+            BuildingKind::Market => self.furnish_market(&mut map),
+            BuildingKind::Blacksmith => self.furnish_smithy(&mut map),
+            BuildingKind::Storage => self.furnish_storage(&mut map),
+        }
+
+        map
+    }
+
+    // The interior plus any floors above it: canopy platforms over elven
+    // treehouses and a bell tower over temples.
+    pub fn generate_floors(&mut self) -> Vec<LocationMap> {
+        let mut ground = self.generate();
+
+        let above = match self.kind {
+            BuildingKind::Treehouse => Some((LevelKind::Canopy, self.rng.gen_range(1..=2))),
+            BuildingKind::Temple => Some((LevelKind::Tower, 1)),
+            _ => None,
+        };
+
+        let mut floors = Vec::new();
+        if let Some((kind, count)) = above {
+            let entry = match kind {
+                LevelKind::Tower => Position { x: 1, y: 1 },
+                _ => Position { x: ground.width - 2, y: ground.height - 2 },
+            };
+            let mut level_generator = LevelGenerator::new(self.rng.r#gen(), kind);
+            floors = level_generator.generate(&mut ground, entry, count);
+        }
+        floors.insert(0, ground);
+        floors
+    }
+
+    fn determine_size(&mut self) -> (usize, usize) {
+        let (w, h) = match self.kind {
+            BuildingKind::House => (9, 7),
+            BuildingKind::Treehouse => (7, 7),
+            BuildingKind::Hut => (7, 5),
+            BuildingKind::Tavern => (15, 11),
+            BuildingKind::Temple => (11, 13),
+            BuildingKind::Market => (15, 9),
+            BuildingKind::Blacksmith => (11, 9),
+            BuildingKind::Storage => (9, 7),
+        };
+        // Keep the width odd so the door sits in the middle of the wall
+        let extra = self.rng.gen_range(0..=1) * 2;
+        (w + extra, h)
+    }
+
+    fn create_walled_room(&self, width: usize, height: usize) -> LocationMap {
+        let mut tiles = vec![vec![LocationTile {
+            blocked: false,
+            tile_type: LocationTileType::Floor,
+            feature: None,
+            seen: false,
+        }; width]; height];
+
+        for row in [0, height - 1] {
+            for tile in tiles[row].iter_mut() {
+                tile.tile_type = LocationTileType::Wall;
+            }
+        }
+        for row in tiles.iter_mut() {
+            row[0].tile_type = LocationTileType::Wall;
+            row[width - 1].tile_type = LocationTileType::Wall;
+        }
+        tiles[height - 1][width / 2].tile_type = LocationTileType::Door;
+
+        LocationMap {
+            name: capitalize(self.kind.name()),
+            level: 0,
+            width,
+            height,
+            tiles,
+            points_of_interest: Vec::new(),
+            npc_spots: Vec::new(),
+            visible: HashSet::new(),
+        }
+    }
+
+    fn furnish_house(&mut self, map: &mut LocationMap) {
+        let mid_x = map.width / 2;
+        self.set(map, 1, 1, LocationTileType::Bed);
+        if self.rng.gen_bool(0.5) {
+            self.set(map, map.width - 2, 1, LocationTileType::Bed);
+        }
+        self.set(map, mid_x, 1, LocationTileType::Hearth);
+        self.set(map, mid_x, map.height / 2, LocationTileType::Table);
+        self.add_npc(map, mid_x + 1, map.height / 2, NpcRole::Resident);
+    }
+
+    fn furnish_hut(&mut self, map: &mut LocationMap) {
+        let mid_x = map.width / 2;
+        self.set(map, mid_x, map.height / 2 - 1, LocationTileType::Hearth);
+        self.set(map, 1, 1, LocationTileType::Bed);
+        self.add_npc(map, map.width - 2, 1, NpcRole::Resident);
+    }
+
+    fn furnish_tavern(&mut self, map: &mut LocationMap) {
+        // Bar counter across the back with the innkeeper behind it
+        for x in 2..map.width - 2 {
+            self.set(map, x, 2, LocationTileType::Counter);
+        }
+        self.add_npc(map, map.width / 2, 1, NpcRole::Innkeeper);
+        self.set(map, map.width - 2, 1, LocationTileType::Shelf);
+        self.set(map, 1, map.height / 2, LocationTileType::Hearth);
+
+        // Tables with benches and the odd patron in the common room
+        let num_tables = self.rng.gen_range(3..=5);
+        for _ in 0..num_tables {
+            let x = self.rng.gen_range(2..map.width - 2);
+            let y = self.rng.gen_range(4..map.height - 3);
+            if !self.is_free(map, x, y) || !self.is_free(map, x + 1, y) {
+                continue;
+            }
+            self.set(map, x, y, LocationTileType::Table);
+            self.set(map, x + 1, y, LocationTileType::Bench);
+            if self.rng.gen_bool(0.6) {
+                self.add_npc(map, x - 1, y, NpcRole::Patron);
+            }
+        }
+    }
+
+    fn furnish_temple(&mut self, map: &mut LocationMap) {
+        let mid_x = map.width / 2;
+        self.set(map, mid_x, 2, LocationTileType::Altar);
+        self.add_npc(map, mid_x, 1, NpcRole::Priest);
+
+        // Rows of benches with an aisle down the middle
+        for y in (4..map.height - 2).step_by(2) {
+            for x in 2..map.width - 2 {
+                if x != mid_x {
+                    self.set(map, x, y, LocationTileType::Bench);
+                }
+            }
+        }
+    }
+
+    fn furnish_market(&mut self, map: &mut LocationMap) {
+        // Stalls in two rows, each with a merchant behind the counter
+        for y in [2, map.height - 4] {
+            let mut x = 2;
+            while x + 2 < map.width - 1 {
+                self.set(map, x, y, LocationTileType::Counter);
+                self.set(map, x + 1, y, LocationTileType::Counter);
+                if self.rng.gen_bool(0.8) {
+                    self.add_npc(map, x, y - 1, NpcRole::Merchant);
+                }
+                x += 4;
+            }
+        }
+    }
+
+    fn furnish_smithy(&mut self, map: &mut LocationMap) {
+        let mid_x = map.width / 2;
+        self.set(map, 1, 1, LocationTileType::Hearth);
+        self.set(map, 2, 1, LocationTileType::Hearth);
+        self.set(map, 2, 3, LocationTileType::Anvil);
+        self.add_npc(map, 3, 3, NpcRole::Smith);
+        for x in mid_x..map.width - 2 {
+            self.set(map, x, map.height - 3, LocationTileType::Counter);
+        }
+        for x in mid_x + 1..map.width - 1 {
+            self.set(map, x, 1, LocationTileType::Shelf);
+        }
+    }
+
+    fn furnish_storage(&mut self, map: &mut LocationMap) {
+        for x in 1..map.width - 1 {
+            self.set(map, x, 1, LocationTileType::Shelf);
+        }
+        for y in 2..map.height - 2 {
+            if self.rng.gen_bool(0.6) {
+                self.set(map, 1, y, LocationTileType::Shelf);
+            }
+            if self.rng.gen_bool(0.6) {
+                self.set(map, map.width - 2, y, LocationTileType::Shelf);
+            }
+        }
+        self.add_npc(map, map.width / 2, map.height / 2, NpcRole::Keeper);
+    }
+
+    fn set(&self, map: &mut LocationMap, x: usize, y: usize, tile_type: LocationTileType) {
+        if x > 0 && y > 0 && x < map.width - 1 && y < map.height - 1 {
+            map.tiles[y][x].blocked = tile_type.is_blocking();
+            map.tiles[y][x].tile_type = tile_type;
+        }
+    }
+
+    fn is_free(&self, map: &LocationMap, x: usize, y: usize) -> bool {
+        x > 0 && y > 0 && x < map.width - 1 && y < map.height - 1
+            && map.tiles[y][x].tile_type == LocationTileType::Floor
+            && map.npc_at(&Position { x, y }).is_none()
+    }
+
+    fn add_npc(&self, map: &mut LocationMap, x: usize, y: usize, role: NpcRole) {
+        if self.is_free(map, x, y) {
+            map.npc_spots.push(NpcSpot { position: Position { x, y }, role });
+        }
+    }
+}
+
+impl LocationMap {
+    // Interiors are entered through their door, so spawn on the tile just inside it
+    pub fn find_entrance(&self) -> Position {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.tiles[y][x].tile_type == LocationTileType::Door {
+                    return Position { x, y: y.saturating_sub(1) };
+                }
+            }
+        }
+        self.find_spawn_position()
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::location_generator::Feature;
+
+    const KINDS: [BuildingKind; 8] = [
+        BuildingKind::House,
+        BuildingKind::Treehouse,
+        BuildingKind::Hut,
+        BuildingKind::Tavern,
+        BuildingKind::Temple,
+        BuildingKind::Market,
+        BuildingKind::Blacksmith,
+        BuildingKind::Storage,
+    ];
+
+    #[test]
+    fn every_interior_is_entered_through_its_door() {
+        for kind in KINDS {
+            for seed in 0..20 {
+                let map = InteriorGenerator::new(seed, kind).generate();
+                let door = Position { x: map.width / 2, y: map.height - 1 };
+                assert!(map.tiles[door.y][door.x].tile_type == LocationTileType::Door, "{kind:?} has no door");
+                let entrance = map.find_entrance();
+                assert_eq!(entrance, Position { x: door.x, y: door.y - 1 });
+                assert!(map.is_walkable(entrance.x, entrance.y), "{kind:?} is blocked inside the door");
+                // Everyone inside stands on open floor
+                for npc in &map.npc_spots {
+                    assert!(map.is_walkable(npc.position.x, npc.position.y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_builds_the_same_interior() {
+        for kind in KINDS {
+            let first = InteriorGenerator::new(7, kind).generate_floors();
+            let second = InteriorGenerator::new(7, kind).generate_floors();
+            assert!(first == second, "{kind:?} changed between visits");
+        }
+    }
+
+    #[test]
+    fn features_decide_what_a_building_is() {
+        let mut tile = LocationTile {
+            blocked: true,
+            tile_type: LocationTileType::HumanHouse,
+            feature: None,
+            seen: false,
+        };
+        assert_eq!(BuildingKind::for_tile(&tile), Some(BuildingKind::House));
+        tile.feature = Some(Feature { name: "Forge".to_string(), feature_type: FeatureType::Blacksmith });
+        assert_eq!(BuildingKind::for_tile(&tile), Some(BuildingKind::Blacksmith));
+        // Gardens are open to the sky, so the house underneath is what you enter
+        tile.feature = Some(Feature { name: "Herbs".to_string(), feature_type: FeatureType::Garden });
+        assert_eq!(BuildingKind::for_tile(&tile), Some(BuildingKind::House));
+        tile.tile_type = LocationTileType::Ground;
+        assert_eq!(BuildingKind::for_tile(&tile), None);
+    }
+}
@@ -0,0 +1,317 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use crate::generators::location_generator::{LocationMap, LocationTile, LocationTileType};
+use crate::systems::position::Position;
+use std::collections::HashSet;
+
+// Extra floors stacked above or below a location map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelKind {
+    Mine,
+    Dungeon,
+    Canopy,
+    Tower,
+}
+
+impl LevelKind {
+    pub fn name(&self) -> &str {
+        match self {
+            LevelKind::Mine => "Mine",
+            LevelKind::Dungeon => "Dungeon",
+            LevelKind::Canopy => "Canopy",
+            LevelKind::Tower => "Tower",
+        }
+    }
+
+    pub fn goes_down(&self) -> bool {
+        matches!(self, LevelKind::Mine | LevelKind::Dungeon)
+    }
+}
+
+pub struct LevelGenerator {
+    rng: StdRng,
+    kind: LevelKind,
+}
+
+impl LevelGenerator {
+    pub fn new(seed: u64, kind: LevelKind) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            kind,
+        }
+    }
+
+    // Build `count` floors above or below `base`, starting with a staircase at
+    // `entry`. Stairs line up between floors: a down staircase at (x, y)
+    // arrives on the up staircase at (x, y) of the floor below.
+    pub fn generate(&mut self, base: &mut LocationMap, entry: Position, count: usize) -> Vec<LocationMap> {
+        let (width, height) = self.determine_size(base);
+        let step = if self.kind.goes_down() { -1 } else { 1 };
+        let (stairs_out, stairs_back) = if self.kind.goes_down() {
+            (LocationTileType::StairsDown, LocationTileType::StairsUp)
+        } else {
+            (LocationTileType::StairsUp, LocationTileType::StairsDown)
+        };
+
+        set_tile(base, entry, stairs_out.clone());
+
+        let mut floors = Vec::with_capacity(count);
+        let mut start = entry;
+        for i in 0..count {
+            let level = base.level + step * (i as i32 + 1);
+            let name = format!("{} {}", base.name, self.kind.name());
+            let mut floor = filled_map(name, width, height, level, self.fill_tile());
+
+            let exit = self.carve(&mut floor, start);
+            set_tile(&mut floor, start, stairs_back.clone());
+
+            let is_last = i + 1 == count;
+            match exit {
+                Some(exit) if !is_last => {
+                    set_tile(&mut floor, exit, stairs_out.clone());
+                    floors.push(floor);
+                    start = exit;
+                }
+                _ => {
+                    floors.push(floor);
+                    break;
+                }
+            }
+        }
+        floors
+    }
+
+    fn determine_size(&self, base: &LocationMap) -> (usize, usize) {
+        match self.kind {
+            // Underground levels sprawl beyond the settlement above them
+            LevelKind::Mine | LevelKind::Dungeon => (base.width.max(32), base.height.max(20)),
+            LevelKind::Canopy | LevelKind::Tower => (base.width, base.height),
+        }
+    }
+
+    fn fill_tile(&self) -> LocationTileType {
+        match self.kind {
+            LevelKind::Canopy => LocationTileType::Foliage,
+            _ => LocationTileType::Wall,
+        }
+    }
+
+    // Carve the walkable part of a floor, connected to `start`. Returns where
+    // the stairs to the next floor should go.
+    fn carve(&mut self, map: &mut LocationMap, start: Position) -> Option<Position> {
+        match self.kind {
+            LevelKind::Mine => self.carve_tunnels(map, start),
+            LevelKind::Dungeon => self.carve_rooms(map, start),
+            LevelKind::Canopy => self.carve_platforms(map, start),
+            LevelKind::Tower => self.carve_tower(map, start),
+        }
+    }
+
+    // Winding tunnels from a drunkard's walk
+    fn carve_tunnels(&mut self, map: &mut LocationMap, start: Position) -> Option<Position> {
+        let target = map.width * map.height / 3;
+        let mut carved = 0;
+        let mut pos = start;
+        let mut farthest = (start, 0);
+
+        for _ in 0..target * 20 {
+            if carved >= target {
+                break;
+            }
+            if map.tiles[pos.y][pos.x].tile_type == LocationTileType::Wall {
+                map.tiles[pos.y][pos.x].tile_type = LocationTileType::Ground;
+                carved += 1;
+            }
+            let dist = manhattan(&pos, &start);
+            if dist > farthest.1 {
+                farthest = (pos, dist);
+            }
+
+            let (dx, dy) = [(0, 1), (1, 0), (0, -1), (-1, 0)][self.rng.gen_range(0..4)];
+            let nx = (pos.x as i32 + dx).clamp(1, map.width as i32 - 2) as usize;
+            let ny = (pos.y as i32 + dy).clamp(1, map.height as i32 - 2) as usize;
+            pos = Position { x: nx, y: ny };
+        }
+
+        (farthest.1 > 0).then_some(farthest.0)
+    }
+
+    // Rectangular rooms joined by corridors, the first one around `start`
+    fn carve_rooms(&mut self, map: &mut LocationMap, start: Position) -> Option<Position> {
+        self.carve_room(map, start, 2, 2);
+        let mut previous = start;
+
+        let num_rooms = self.rng.gen_range(4..=6);
+        for _ in 0..num_rooms {
+            let center = Position {
+                x: self.rng.gen_range(3..map.width - 3),
+                y: self.rng.gen_range(3..map.height - 3),
+            };
+            let half_w = self.rng.gen_range(1..=3);
+            let half_h = self.rng.gen_range(1..=2);
+            self.carve_room(map, center, half_w, half_h);
+            self.carve_corridor(map, previous, center);
+            previous = center;
+        }
+
+        (previous != start).then_some(previous)
+    }
+
+    fn carve_room(&self, map: &mut LocationMap, center: Position, half_w: usize, half_h: usize) {
+        let x0 = center.x.saturating_sub(half_w).max(1);
+        let y0 = center.y.saturating_sub(half_h).max(1);
+        let x1 = (center.x + half_w).min(map.width - 2);
+        let y1 = (center.y + half_h).min(map.height - 2);
+        for row in map.tiles.iter_mut().take(y1 + 1).skip(y0) {
+            for tile in row.iter_mut().take(x1 + 1).skip(x0) {
+                tile.tile_type = LocationTileType::Floor;
+            }
+        }
+    }
+
+    fn carve_corridor(&mut self, map: &mut LocationMap, from: Position, to: Position) {
+        // Go horizontal then vertical, or the other way round
+        let corner = if self.rng.gen_bool(0.5) {
+            Position { x: to.x, y: from.y }
+        } else {
+            Position { x: from.x, y: to.y }
+        };
+        for (a, b) in [(from, corner), (corner, to)] {
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    if map.tiles[y][x].tile_type.is_blocking() {
+                        map.tiles[y][x].tile_type = LocationTileType::Floor;
+                    }
+                }
+            }
+        }
+    }
+
+    // A platform in the branches with a few boughs reaching out
+    fn carve_platforms(&mut self, map: &mut LocationMap, start: Position) -> Option<Position> {
+        self.carve_room(map, start, 1, 1);
+        let mut exit = None;
+
+        let num_boughs = self.rng.gen_range(2..=3);
+        for _ in 0..num_boughs {
+            let end = Position {
+                x: self.rng.gen_range(1..map.width - 1),
+                y: self.rng.gen_range(1..map.height - 1),
+            };
+            self.carve_corridor(map, start, end);
+            if manhattan(&end, &start) >= 2 {
+                exit = Some(end);
+            }
+        }
+        exit
+    }
+
+    // A plain walled room, with the next flight of stairs in the far corner
+    fn carve_tower(&mut self, map: &mut LocationMap, start: Position) -> Option<Position> {
+        for y in 1..map.height - 1 {
+            for x in 1..map.width - 1 {
+                map.tiles[y][x].tile_type = LocationTileType::Floor;
+            }
+        }
+        let exit = Position {
+            x: map.width - 1 - start.x.clamp(1, map.width - 2),
+            y: map.height - 1 - start.y.clamp(1, map.height - 2),
+        };
+        (exit != start).then_some(exit)
+    }
+}
+
+fn filled_map(name: String, width: usize, height: usize, level: i32, fill: LocationTileType) -> LocationMap {
+    LocationMap {
+        name,
+        level,
+        width,
+        height,
+        tiles: vec![vec![LocationTile {
+            blocked: false,
+            tile_type: fill,
+            feature: None,
+            seen: false,
+        }; width]; height],
+        points_of_interest: Vec::new(),
+        npc_spots: Vec::new(),
+        visible: HashSet::new(),
+    }
+}
+
+fn set_tile(map: &mut LocationMap, pos: Position, tile_type: LocationTileType) {
+    if pos.x < map.width && pos.y < map.height {
+        map.tiles[pos.y][pos.x].tile_type = tile_type;
+        map.tiles[pos.y][pos.x].feature = None;
+    }
+}
+
+fn manhattan(a: &Position, b: &Position) -> usize {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    const KINDS: [LevelKind; 4] = [LevelKind::Mine, LevelKind::Dungeon, LevelKind::Canopy, LevelKind::Tower];
+
+    fn find(map: &LocationMap, tile_type: LocationTileType) -> Option<Position> {
+        (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position { x, y }))
+            .find(|pos| map.tiles[pos.y][pos.x].tile_type == tile_type)
+    }
+
+    fn reachable(map: &LocationMap, from: Position, to: Position) -> bool {
+        let mut seen = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(pos) = queue.pop_front() {
+            if pos == to {
+                return true;
+            }
+            for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                let (x, y) = (pos.x as i32 + dx, pos.y as i32 + dy);
+                if !map.in_bounds(x, y) {
+                    continue;
+                }
+                let next = Position { x: x as usize, y: y as usize };
+                if map.is_walkable(next.x, next.y) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn stairs_line_up_between_floors() {
+        for kind in KINDS {
+            for seed in 0..20 {
+                let mut base = filled_map("Testford".to_string(), 20, 12, 0, LocationTileType::Ground);
+                let entry = Position { x: 5, y: 5 };
+                let floors = LevelGenerator::new(seed, kind).generate(&mut base, entry, 3);
+                let (out, back) = if kind.goes_down() {
+                    (LocationTileType::StairsDown, LocationTileType::StairsUp)
+                } else {
+                    (LocationTileType::StairsUp, LocationTileType::StairsDown)
+                };
+                assert!(base.tiles[entry.y][entry.x].tile_type == out);
+
+                let mut arrival = entry;
+                for (i, floor) in floors.iter().enumerate() {
+                    let step = if kind.goes_down() { -1 } else { 1 };
+                    assert_eq!(floor.level, step * (i as i32 + 1));
+                    assert!(floor.tiles[arrival.y][arrival.x].tile_type == back, "{kind:?} floor {i} has no way back");
+                    match find(floor, out.clone()) {
+                        Some(exit) => {
+                            assert!(reachable(floor, arrival, exit), "{kind:?} floor {i} cuts off its stairs");
+                            arrival = exit;
+                        }
+                        // Only the last floor is a dead end
+                        None => assert_eq!(i + 1, floors.len()),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,25 @@
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use crate::systems::world::TerrainType;
-use crate::systems::location::{Location, Species};
+use crate::systems::location::{Industry, Location, LocationState, Species};
+use crate::generators::level_generator::{LevelGenerator, LevelKind};
 use crate::systems::position::Position;
+use crate::systems::fov::compute_fov;
+use std::collections::HashSet;
 use noise::NoiseFn;
+use serde::Deserialize;
 
 #[derive(PartialEq)]
 pub struct LocationMap {
+    pub name: String,
+    // 0 is ground level, negative floors are below ground
+    pub level: i32,
     pub width: usize,
     pub height: usize,
     pub tiles: Vec<Vec<LocationTile>>,
     pub points_of_interest: Vec<PointOfInterest>,
+    pub npc_spots: Vec<NpcSpot>,
+    // Tiles currently in the player's line of sight
+    pub visible: HashSet<Position>,
 }
 
 impl LocationMap {
@@ -46,13 +56,43 @@ impl LocationMap {
     }
 
     pub fn is_walkable(&self, x: usize, y: usize) -> bool {
-        let tile = &self.tiles[y][x];
-        !matches!(tile.tile_type, 
-            LocationTileType::Wall | 
-            LocationTileType::HumanHouse | 
-            LocationTileType::ElfTreehouse | 
-            LocationTileType::OrcHut
-        )
+        !self.tiles[y][x].tile_type.is_blocking()
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+    }
+
+    pub fn npc_at(&self, pos: &Position) -> Option<&NpcSpot> {
+        self.npc_spots.iter().find(|npc| npc.position == *pos)
+    }
+
+    pub fn update_visibility(&mut self, position: &Position, radius: i32) {
+        let (px, py) = (position.x as i32, position.y as i32);
+        let mut visible = HashSet::new();
+
+        compute_fov(
+            radius,
+            &mut |dx, dy| {
+                let (x, y) = (px + dx, py + dy);
+                !self.in_bounds(x, y) || self.tiles[y as usize][x as usize].tile_type.blocks_sight()
+            },
+            &mut |dx, dy| {
+                let (x, y) = (px + dx, py + dy);
+                if self.in_bounds(x, y) {
+                    visible.insert(Position { x: x as usize, y: y as usize });
+                }
+            },
+        );
+
+        for pos in &visible {
+            self.tiles[pos.y][pos.x].seen = true;
+        }
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self, pos: &Position) -> bool {
+        self.visible.contains(pos)
     }
 }
 
@@ -62,6 +102,7 @@ pub struct LocationTile {
     pub blocked: bool,
     pub tile_type: LocationTileType,
     pub feature: Option<Feature>,
+    pub seen: bool,
 }
 
 #[derive(Clone, PartialEq)]
@@ -82,6 +123,60 @@ pub enum LocationTileType {
     OrcHut,
     Trading,
     Shrine,
+
+    // Building interiors
+    Floor,
+    Door,
+    Table,
+    Bench,
+    Counter,
+    Bed,
+    Hearth,
+    Altar,
+    Anvil,
+    Shelf,
+
+    // Multi-level locations
+    StairsUp,
+    StairsDown,
+    Foliage,
+}
+
+impl LocationTileType {
+    pub fn is_blocking(&self) -> bool {
+        matches!(self,
+            LocationTileType::Wall |
+            LocationTileType::HumanHouse |
+            LocationTileType::ElfTreehouse |
+            LocationTileType::OrcHut |
+            LocationTileType::Table |
+            LocationTileType::Counter |
+            LocationTileType::Bed |
+            LocationTileType::Hearth |
+            LocationTileType::Altar |
+            LocationTileType::Anvil |
+            LocationTileType::Shelf |
+            LocationTileType::Foliage
+        )
+    }
+
+    pub fn blocks_sight(&self) -> bool {
+        matches!(self,
+            LocationTileType::Wall |
+            LocationTileType::HumanHouse |
+            LocationTileType::ElfTreehouse |
+            LocationTileType::OrcHut |
+            LocationTileType::Foliage
+        )
+    }
+
+    pub fn is_building(&self) -> bool {
+        matches!(self,
+            LocationTileType::HumanHouse |
+            LocationTileType::ElfTreehouse |
+            LocationTileType::OrcHut
+        )
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -90,7 +185,8 @@ pub struct Feature {
     pub feature_type: FeatureType,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FeatureType {
     Market,
     Temple,
@@ -106,6 +202,38 @@ pub struct PointOfInterest {
     pub feature: Feature,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NpcRole {
+    Resident,
+    Innkeeper,
+    Patron,
+    Priest,
+    Merchant,
+    Smith,
+    Keeper,
+}
+
+impl NpcRole {
+    pub fn name(&self) -> &str {
+        match self {
+            NpcRole::Resident => "resident",
+            NpcRole::Innkeeper => "innkeeper",
+            NpcRole::Patron => "patron",
+            NpcRole::Priest => "priest",
+            NpcRole::Merchant => "merchant",
+            NpcRole::Smith => "smith",
+            NpcRole::Keeper => "storekeeper",
+        }
+    }
+}
+
+// A spot inside a location where an NPC stands
+#[derive(Clone, PartialEq)]
+pub struct NpcSpot {
+    pub position: Position,
+    pub role: NpcRole,
+}
+
 pub struct LocationGenerator {
     rng: StdRng,
     base_terrain: TerrainType,
@@ -136,6 +264,43 @@ impl LocationGenerator {
         map
     }
 
+    // The settlement plus any floors beneath it: mines under mining towns and
+    // dungeons under ruined or forsaken places.
+    pub fn generate_floors(&mut self) -> Vec<LocationMap> {
+        let mut ground = self.generate();
+
+        let below = match (self.location.state, self.location.industry) {
+            (LocationState::Ruins | LocationState::Abandoned |
+             LocationState::Cursed | LocationState::Hidden, _) => {
+                Some((LevelKind::Dungeon, self.rng.gen_range(2..=4)))
+            }
+            (_, Industry::Mining) => Some((LevelKind::Mine, self.rng.gen_range(2..=3))),
+            _ => None,
+        };
+
+        let mut floors = Vec::new();
+        if let Some((kind, count)) = below {
+            let entry = self.find_stairs_position(&ground);
+            let mut level_generator = LevelGenerator::new(self.rng.r#gen(), kind);
+            floors = level_generator.generate(&mut ground, entry, count);
+        }
+        floors.insert(0, ground);
+        floors
+    }
+
+    fn find_stairs_position(&mut self, map: &LocationMap) -> Position {
+        // Somewhere quiet: plain ground away from roads and features
+        for _ in 0..100 {
+            let x = self.rng.gen_range(1..map.width - 1);
+            let y = self.rng.gen_range(1..map.height - 1);
+            let tile = &map.tiles[y][x];
+            if tile.tile_type == LocationTileType::Ground && tile.feature.is_none() {
+                return Position { x, y };
+            }
+        }
+        map.find_spawn_position()
+    }
+
     fn determine_map_size(&mut self) -> (usize, usize) {
         // Base size on location.size, cast to i32 to handle negative values
         let base_size = (self.location.size as f32).sqrt() as i32;
@@ -151,10 +316,18 @@ impl LocationGenerator {
         self.place_central_features(map);
         // Add houses along roads
         self.place_houses(map);
+        // Merchant towns line their roads with stalls
+        if self.location.industry == Industry::Trading {
+            self.place_trading_posts(map);
+        }
         // Add defensive walls if size > threshold
         if self.location.size > 50 {
             self.add_walls(map);
         }
+        // Bigger towns keep a yard for drilling the guard
+        if self.location.size > 200 {
+            self.place_training_ground(map);
+        }
     }
 
     fn generate_elf_settlement(&mut self, map: &mut LocationMap) {
@@ -170,6 +343,9 @@ impl LocationGenerator {
         // Basic settlement generation
         self.generate_road_network(map);
         self.place_central_features(map);
+        if self.location.industry == Industry::Trading {
+            self.place_trading_posts(map);
+        }
     }
 
     fn create_empty_map(&self, width: usize, height: usize) -> LocationMap {
@@ -177,13 +353,18 @@ impl LocationGenerator {
             blocked: false,
             tile_type: LocationTileType::Ground,
             feature: None,
+            seen: false,
         }; width]; height];
 
         LocationMap {
+            name: self.location.name.clone(),
+            level: 0,
             width,
             height,
             tiles,
             points_of_interest: Vec::new(),
+            npc_spots: Vec::new(),
+            visible: HashSet::new(),
         }
     }
 
@@ -377,6 +558,42 @@ impl LocationGenerator {
         }
     }
 
+    // A few stalls on open ground by the roads, where goods can be traded
+    fn place_trading_posts(&mut self, map: &mut LocationMap) {
+        let wanted = (map.width * map.height / 100).max(2);
+        let mut placed = 0;
+        for _ in 0..100 {
+            if placed == wanted {
+                return;
+            }
+            let x = self.rng.gen_range(1..map.width - 1);
+            let y = self.rng.gen_range(1..map.height - 1);
+            if map.tiles[y][x].tile_type == LocationTileType::Ground
+                && map.tiles[y][x].feature.is_none()
+                && self.is_adjacent_to_road(map, x, y)
+            {
+                map.tiles[y][x].tile_type = LocationTileType::Trading;
+                placed += 1;
+            }
+        }
+    }
+
+    // An open patch of ground by a road, away from the other features
+    fn place_training_ground(&mut self, map: &mut LocationMap) {
+        for _ in 0..100 {
+            let x = self.rng.gen_range(1..map.width - 1);
+            let y = self.rng.gen_range(1..map.height - 1);
+            let pos = Position { x, y };
+            if map.tiles[y][x].tile_type == LocationTileType::Ground
+                && self.is_adjacent_to_road(map, x, y)
+                && !self.is_near_feature(map, pos, 2)
+            {
+                self.place_feature(map, pos, FeatureType::TrainingGround);
+                return;
+            }
+        }
+    }
+
     fn place_feature(&mut self, map: &mut LocationMap, pos: Position, feature_type: FeatureType) {
         if pos.x < map.width && pos.y < map.height {
             let feature = Feature {
@@ -409,7 +626,21 @@ impl LocationGenerator {
             FeatureType::Temple => "Sacred Temple".to_string(),
             FeatureType::Tavern => "The Wanderer's Rest".to_string(),
             FeatureType::Garden => "Natural Garden".to_string(),
-            _ => "Feature".to_string(),
+            FeatureType::Blacksmith => "Blacksmith's Forge".to_string(),
+            FeatureType::TrainingGround => "Training Ground".to_string(),
+            FeatureType::Storage => "Storehouse".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+// Derive a stable seed for one location from the world seed and its
+// coordinates, so every place has its own layout that stays the same
+// between visits.
+pub fn location_seed(world_seed: u64, parts: &[usize]) -> u64 {
+    let mut seed = world_seed;
+    for &value in parts {
+        seed = (seed ^ value as u64).wrapping_mul(0x100_0000_01b3);
+        seed ^= seed >> 29;
+    }
+    seed
+}
@@ -1,2 +1,4 @@
 pub mod world_generator;
-pub mod location_generator;
\ No newline at end of file
+pub mod location_generator;
+pub mod interior_generator;
+pub mod level_generator;
@@ -5,12 +5,17 @@ use rand::RngCore;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 
 use rand::seq::SliceRandom; // ← add this at the top
-use std::collections::{VecDeque, HashSet};
+use std::collections::{VecDeque, HashSet, HashMap};
+#[cfg(not(test))]
 use crate::dump_noise_png;
+use crate::systems::position::Position;
 use crate::systems::world::{World, Tile, TileGrid, TerrainType};
 use crate::systems::location::{Location, Species, Governance, LocationState, Industry};
+use crate::systems::settlement::starting_prosperity;
+use crate::generators::location_generator::location_seed;
 
 pub struct WorldGenerator {
+    seed: u32,
     height_noise: Perlin,
     biome_noise: Perlin,
     temperature_noise: Perlin,
@@ -24,6 +29,9 @@ pub struct WorldGenerator {
 }
 
 const OCEAN_LEVEL: f64 = 0.5; // Adjusted ocean level for more water
+// Each settlement lays a road to the nearest other one it can walk to in
+// this many steps
+const ROAD_REACH: usize = 12;
 
 /// Given a list of all candidate sources `(x,y)`, return a subset
     /// such that no two chosen sources are within `min_dist` tiles (Chebyshev).
@@ -58,6 +66,7 @@ impl WorldGenerator {
      pub fn new(seed: u32, width: usize, height: usize) -> Self {
         let mut rng = StdRng::seed_from_u64(seed as u64);
         WorldGenerator {
+            seed,
             height_noise: Perlin::new(seed),
             biome_noise: Perlin::new(seed + 1),
             temperature_noise: Perlin::new(seed + 2),
@@ -97,6 +106,8 @@ impl WorldGenerator {
                 terrain_map[y][x] = self.determine_biome(h, t, m);
             }
         }
+        // Debug dumps; tests generate many worlds and must not touch the tracked images
+        #[cfg(not(test))]
         dump_noise_png(&heights, "heightmap.png").expect("Failed to save heightmap");
         
         let freq = 0.02; // adjust to taste: larger → thinner ridges
@@ -121,6 +132,7 @@ impl WorldGenerator {
                     .collect()
             })
             .collect();
+            #[cfg(not(test))]
             dump_noise_png(&raw_river, "river_map.png").expect("Failed to save river map");
 
             // 2) Convert that into a boolean `river_map`: true wherever ridged > threshold
@@ -139,14 +151,20 @@ impl WorldGenerator {
             let low  = self.rng.next_u32() as u64;
             (high << 32) ^ low
         };
-        World {
+        let mut world = World {
             seed: combined_seed,
+            generation_seed: self.seed,
             width: self.width,
             height: self.height,
             wraparound: true,
             tiles,
-        }
-    
+            visible: HashSet::new(),
+            surveyed_from: None,
+        };
+
+        // 6) ROADS between neighbouring settlements
+        lay_roads(&mut world);
+        world
     }
 
     // turned into a generic noise function
@@ -225,7 +243,7 @@ impl WorldGenerator {
                         if !blocked {
                             let roll = self.rng.gen_range(0.0..1.0);
                             if roll < 0.05 {
-                                location = Some(self.generate_location(final_terrain));
+                                location = Some(self.generate_location(final_terrain, x, y));
                             }
                         }
 
@@ -243,7 +261,13 @@ impl WorldGenerator {
     }
 
 
-    fn generate_location(&mut self, terrain: TerrainType) -> Location {
+    // How a settlement of `species` is ruled. Drawn from its own seed so the
+    // rest of the world comes out the same whatever is picked.
+    fn pick_governance(species: Species, seed: u64) -> Governance {
+        *species.governances().choose(&mut StdRng::seed_from_u64(seed)).expect("every species has a way to rule itself")
+    }
+
+    fn generate_location(&mut self, terrain: TerrainType, x: usize, y: usize) -> Location {
         let species = match terrain {
             TerrainType::Plains | TerrainType::Forest => {
                 if self.rng.gen_bool(0.5) { Species::Human } else { Species::Elf }
@@ -339,8 +363,11 @@ impl WorldGenerator {
         species,
         state,
         size,
-        governance: Governance::Democracy,
-        industry: Industry::Farming,
+        governance: Self::pick_governance(species, location_seed(self.seed as u64, &[x, y])),
+        industry,
+        searched: false,
+        prosperity: starting_prosperity(state),
+        revealed: state != LocationState::Hidden,
     }
 }
 
@@ -365,6 +392,53 @@ fn generate_river_map(&mut self) -> Vec<Vec<bool>> {
     }
 
 }
+
+// Link every settlement to the closest other one within ROAD_REACH steps,
+// turning the shortest way between them into road
+fn lay_roads(world: &mut World) {
+    let settlements: Vec<Position> = (0..world.height)
+        .flat_map(|y| (0..world.width).map(move |x| Position::new(x, y)))
+        .filter(|pos| world.get_tile(pos).location.is_some())
+        .collect();
+
+    for start in settlements {
+        // Breadth-first, so the first settlement reached is the closest
+        let mut came_from: HashMap<Position, Position> = HashMap::new();
+        let mut frontier = VecDeque::from([(start, 0)]);
+        let mut found = None;
+        while let Some((current, steps)) = frontier.pop_front() {
+            if current != start && world.get_tile(&current).location.is_some() {
+                found = Some(current);
+                break;
+            }
+            if steps == ROAD_REACH {
+                continue;
+            }
+            for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                let Some(next) = world.neighbour(&current, dx, dy) else {
+                    continue;
+                };
+                if next == start || world.get_tile(&next).blocked || came_from.contains_key(&next) {
+                    continue;
+                }
+                came_from.insert(next, current);
+                frontier.push_back((next, steps + 1));
+            }
+        }
+
+        let Some(mut pos) = found else {
+            continue;
+        };
+        while let Some(&previous) = came_from.get(&pos) {
+            let tile = &mut world.tiles[pos.y][pos.x];
+            if tile.location.is_none() {
+                tile.terrain = TerrainType::Road;
+            }
+            pos = previous;
+        }
+    }
+}
+
 fn layered_perlin(x: f64, y: f64, perlin: &Perlin, octaves: usize, persistence: f64, lacunarity: f64) -> f64 {
     let mut total = 0.0;
     let mut frequency = 1.0;  // Changed from 5.0 to 1.0 for larger features
@@ -381,4 +455,26 @@ fn layered_perlin(x: f64, y: f64, perlin: &Perlin, octaves: usize, persistence:
     (total / max_value + 1.0) / 2.0
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn roads_run_between_settlements() {
+        let world = World::new(42, 96, 64);
+        let roads: Vec<Position> = (0..world.height)
+            .flat_map(|y| (0..world.width).map(move |x| Position::new(x, y)))
+            .filter(|pos| world.get_tile(pos).terrain == TerrainType::Road)
+            .collect();
+        assert!(!roads.is_empty());
+        // Every stretch of road carries on or ends at a settlement
+        for pos in roads {
+            let links = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .into_iter()
+                .filter_map(|(dx, dy)| world.neighbour(&pos, dx, dy))
+                .filter(|next| world.get_tile(next).terrain == TerrainType::Road || world.get_tile(next).location.is_some())
+                .count();
+            assert!(links >= 2, "the road at {:?} leads nowhere", pos);
+        }
+    }
+}
@@ -12,14 +12,11 @@ use std::error::Error;
 use std::path::Path;
 use image::{RgbaImage, Rgba};
 
-use core::{game::Game, input::handle_input};
+use core::{game::Game, game_loop, input_source::{CrosstermInput, ScriptedInput}, keymap::{KEYMAP_PATH, Keymap}};
 use render::{Renderer, tui_render::TuiRenderer};
 #[cfg(feature = "bevy-renderer")]
 use render::bevy_render::BevyRenderer;
 
-pub const MAP_WIDTH: usize = 512;
-pub const MAP_HEIGHT: usize = 256;
-
 fn main() {
 
     if let Err(e) = run() {
@@ -30,67 +27,48 @@ fn main() {
     
 }
 fn run() -> Result<()> {
-    let mut renderer: Box<dyn Renderer> = if cfg!(feature = "bevy-renderer") {
-        Box::new(BevyRenderer::new()?)
-    } else {
-        Box::new(TuiRenderer::new()?)
-    };
-    renderer.init()?;
-
-    let mut player = Player::create_random(MAP_WIDTH / 2, MAP_HEIGHT / 2);
-    player.character = Character::create_random(); // or Character::create_human("Name".to_string());
-
-    // Construct initial game state
-    let mut game = Game {
-        player: player,
-        world: World::new(42,MAP_WIDTH, MAP_HEIGHT),
-        view_radius: 10,
-        phase: GamePhase::PlayingWorld,
-        current_message: None,
+    // Read key bindings first so a broken config is reported before the screen is taken over
+    let keymap = Keymap::load(KEYMAP_PATH)?;
+    // `--replay <file>` plays the key names in the file before handing over to the keyboard
+    let replay = match arg_value("--replay") {
+        Some(path) => Some(ScriptedInput::from_script(&std::fs::read_to_string(path)?)?),
+        None => None,
     };
 
-    // Place the player in a town using Position
-    let start_pos = Position {
-        x: MAP_WIDTH / 2,
-        y: MAP_HEIGHT / 2,
-    };
+    #[cfg(feature = "bevy-renderer")]
+    let mut renderer: Box<dyn Renderer> = Box::new(BevyRenderer::new()?);
+    #[cfg(not(feature = "bevy-renderer"))]
+    let mut renderer: Box<dyn Renderer> = Box::new(TuiRenderer::new()?);
+    renderer.init()?;
 
-    if let Some(pos) = game.world.find_nearest_species(&start_pos, game.player.character.species) {
-        println!("Nearest town is at {:?}", pos);
-        game.player.world_pos = pos;
-    } else {
-        println!("No towns found!");
+    // Start at the title menu; the world is generated once a new game begins
+    let mut game = Game::title_screen();
+    game.keymap = keymap;
+    // `--dump-world <file>` draws every new world to a PNG for debugging
+    if let Some(path) = arg_value("--dump-world") {
+        game.on_new_world = Some(Box::new(move |world| dump_png(world, &path)));
     }
 
-    dump_png(&game.world, "world_debug.png").unwrap();
-
-    // Game loop
-    loop {
-        // First render the current game state
-        renderer.render(&game);
-
-        // Then handle the current phase
-        match game.phase {
-            GamePhase::Menu => {
-                // TODO: render menu, handle menu input
-            }
-            GamePhase::PlayingWorld | GamePhase::PlayingLocation(_) => {
-                handle_input(&mut game)?;
-                game.world.update(&game.player.world_pos);
-            }
-            GamePhase::Map => {
-                handle_input(&mut game)?;
-            }
-            GamePhase::GameOver => {
-                break;
-            }
-        }
+    if let Some(mut replay) = replay {
+        game_loop::run(&mut game, renderer.as_mut(), &mut replay)?;
     }
+    game_loop::run(&mut game, renderer.as_mut(), &mut CrosstermInput)?;
+
     // Cleanup and shutdown
     renderer.shutdown()?;
     Ok(())  // Add explicit Ok return
 }
 
+// The value following `flag` on the command line
+fn arg_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+    }
+    None
+}
 
 pub fn dump_png(world: &World, path: impl AsRef<Path>) -> Result<()> {
     let w = world.width as u32;
@@ -6,7 +6,6 @@ pub use crate::systems::world::{World};
 
 // Core
 pub use crate::core::game::{Game, GamePhase};
-pub use crate::core::input::handle_input;
 
 // Render
 pub use crate::render::{Renderer, tui_render::TuiRenderer};
@@ -1,14 +1,35 @@
 use crate::systems::world::{World, TerrainType, Tile};
 use crate::systems::location::{Location, LocationState, Species};
-use crate::generators::location_generator::{LocationMap, LocationTileType, FeatureType};
-
-use crate::core::game::{Game, GamePhase};
+use crate::generators::location_generator::{LocationMap, LocationTile, LocationTileType, FeatureType};
+use crate::generators::interior_generator::BuildingKind;
+
+use crate::core::game::{Game, GamePhase, GameSummary};
+use crate::core::map_view::MapView;
+use crate::core::journal_view::{JournalTab, JournalView};
+use crate::core::character_sheet::CharacterSheet;
+use crate::core::inventory_view::{InventoryRow, InventoryView};
+use crate::core::feature_view::FeatureView;
+use crate::core::menu::{
+    CHARACTER_FIELDS, CharacterField, KEY_PRESETS, MenuScreen, MenuView, NEW_GAME_FIELDS, NewGameField, TITLE_ITEMS,
+    WORLD_SIZES,
+};
+use crate::core::command::{self, Command, Interaction};
+use crate::core::keymap::{KeyContext, Keymap};
+use crate::systems::journal::Journal;
+use crate::systems::character::{ATTRIBUTES, Character};
+use crate::systems::equipment::EQUIP_SLOTS;
+use crate::systems::item::{Item, MAX_CONDITION};
+use crate::systems::status::StatusEffect;
+use crate::systems::species::Attitude;
+use crate::core::trade_view::{TradeSide, TradeView};
+use crate::systems::reputation::Standing;
+use crate::systems::combat::{Combat, CombatAction};
 use crate::systems::player::Player;
 use crate::systems::position::Position;
 use crate::render::Renderer;
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Rect, Layout, Constraint, Direction},
+    backend::{Backend, CrosstermBackend},
+    layout::{Alignment, Rect, Layout, Constraint, Direction},
     style::{Style, Stylize, Color},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Wrap},
@@ -23,13 +44,20 @@ use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
 };
 
-const FOG_RADIUS: i32 = 4;
 const RENDER_RADIUS: i32 = 10;  // Add this constant
+// Width of the progress bar towards the next level
+const XP_BAR_WIDTH: i32 = 20;
+// Price factors beyond which goods are marked cheap or dear on the trade screen
+const CHEAP: f32 = 0.9;
+const DEAR: f32 = 1.1;
 
 // Define a type alias for our terminal type
 pub type GameTerminal = Terminal<CrosstermBackend<std::io::Stdout>>;
-pub struct TuiRenderer {
-    terminal: GameTerminal,
+
+// Draws the game with ratatui. Normally this drives the real terminal, but any
+// backend works, e.g. `TestBackend` to render into a buffer in tests.
+pub struct TuiRenderer<B: Backend = CrosstermBackend<std::io::Stdout>> {
+    terminal: Terminal<B>,
 }
 
 impl TuiRenderer {
@@ -59,23 +87,92 @@ impl TuiRenderer {
         self.terminal.show_cursor()?;
         Ok(())
     }
+}
+
+// Drawing into any backend, e.g. an in-memory one for tests
+#[cfg(test)]
+impl<B: Backend> TuiRenderer<B> {
+    pub fn with_backend(backend: B) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self { terminal: Terminal::new(backend)? })
+    }
+
+    pub fn backend(&self) -> &B {
+        self.terminal.backend()
+    }
+}
 
+impl<B: Backend> TuiRenderer<B> {
     // main render function
     pub fn render(&mut self, game: &Game) -> Result<(), Box<dyn std::error::Error>> {
         // 1) If we're in "Map" phase, draw only the full map and return early.
-        if let GamePhase::Map = &game.phase {
+        if let GamePhase::Map(view) = &game.phase {
             // Grab terminal size BEFORE mutably borrowing it for draw:
             let area = self.terminal.size()?;
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(5)].as_ref())
+                .split(area);
             // Build the full‐map widget:
-            let full_map_widget = self.render_full_map_widget(&game.world, area);
+            let full_map_widget = self.render_full_map_widget(game, view, chunks[0]);
+            let info_widget = self.render_map_info(game, view);
 
             // Now draw it:
             self.terminal.draw(|f| {
-                f.render_widget(full_map_widget, area);
+                f.render_widget(full_map_widget, chunks[0]);
+                f.render_widget(info_widget, chunks[1]);
+            })?;
+            return Ok(());
+        }
+
+        //    The journal also takes over the whole screen.
+        if let GamePhase::Journal(view) = &game.phase {
+            let area = self.terminal.size()?;
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(6)].as_ref())
+                .split(area);
+            let (list_widget, detail_widget) = self.render_journal(game, view, chunks[0]);
+
+            self.terminal.draw(|f| {
+                f.render_widget(list_widget, chunks[0]);
+                f.render_widget(detail_widget, chunks[1]);
             })?;
             return Ok(());
         }
 
+        //    So does a fight.
+        if let GamePhase::Combat(combat) = &game.phase {
+            let actions = Combat::actions(&game.player.character);
+            let area = self.terminal.size()?;
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(actions.len() as u16 + 3)].as_ref())
+                .split(area);
+            let (fight_widget, action_widget) = self.render_combat(game, combat, &actions);
+
+            self.terminal.draw(|f| {
+                f.render_widget(fight_widget, chunks[0]);
+                f.render_widget(action_widget, chunks[1]);
+            })?;
+            return Ok(());
+        }
+
+        //    So do the menus.
+        let menu_widget = match &game.phase {
+            GamePhase::Menu(view) => Some(self.render_menu(game, view)),
+            GamePhase::GameOver(summary) => Some(self.render_game_over(game, summary)),
+            GamePhase::CharacterSheet(sheet) => Some(self.render_character_sheet(game, sheet)),
+            GamePhase::Inventory(view) => Some(self.render_inventory(game, view)),
+            GamePhase::Feature(view) => Some(self.render_feature(game, view)),
+            GamePhase::Trade(view) => Some(self.render_trade(game, view)),
+            GamePhase::Reputation(_) => Some(self.render_reputation(game)),
+            _ => None,
+        };
+        if let Some(menu_widget) = menu_widget {
+            self.terminal.draw(|f| f.render_widget(menu_widget, f.size()))?;
+            return Ok(());
+        }
+
         // 2) For any other phase, we need the 4‐pane layout.
         //    Compute terminal size & layout once up front:
         let size = self.terminal.size()?;
@@ -85,6 +182,7 @@ impl TuiRenderer {
         //    A) Map widget (for GamePhase::PlayingWorld)
         let map_widget = self.render_tile_map(
             &game.world,
+            &game.journal,
             &game.player.world_pos,
             game.view_radius,
             "The World",
@@ -92,18 +190,27 @@ impl TuiRenderer {
 
         // B) Stats widget: build owned Lines via Span::raw(String)
         let stats_widget = {
-            let stats_vec: Vec<String> = vec![
-                format!("Name: {}", game.player.character.name),
-                format!("Species: {}", game.player.character.species),
-                format!("Class: {}", game.player.character.class),
-                format!(
-                    "HP: {}/{}",
-                    game.player.character.health, game.player.character.max_health
-                ),
-                format!("Str: {}", game.player.character.attack),
-                format!("Int: {}", game.player.character.dodge),
-                format!("Dex: {}", game.player.character.luck),
+            let character = &game.player.character;
+            let mut stats_vec: Vec<String> = vec![
+                format!("Name: {}", character.name),
+                format!("Species: {}", character.species),
+                format!("Class: {}", character.class),
+                level_line(character),
+                format!("HP: {}/{}", character.health, character.max_health()),
             ];
+            if let Some(class) = character.class_def() {
+                stats_vec.push(format!("{}: {}/{}", class.resource, character.resource, character.max_resource));
+            }
+            stats_vec.push(format!("Gold: {}", game.player.gold));
+            for attribute in ATTRIBUTES {
+                stats_vec.push(format!("{}: {}", attribute.short_label(), character.attribute(attribute)));
+            }
+            if !character.effects.is_empty() {
+                stats_vec.push(String::new());
+                stats_vec.extend(character.effects.iter().map(StatusEffect::describe));
+            }
+            stats_vec.push(String::new());
+            stats_vec.push(game.clock.describe());
             let lines: Vec<Line> = stats_vec
                 .into_iter()
                 .map(|s| Line::from(Span::raw(s)))
@@ -112,33 +219,48 @@ impl TuiRenderer {
                 .block(Block::default().borders(Borders::ALL).title("Character"))
         };
 
-        //    C) Message widget (if any)
-        let message_widget = if let Some(message) = &game.current_message {
-            Some(
-                Paragraph::new(message.as_str())
-                    .block(Block::default().borders(Borders::ALL).title("Message"))
-                    .wrap(Wrap { trim: true }),
-            )
-        } else {
-            None
-        };
+        //    C) Message widget (if any); the ability list takes its place while open
+        let message_widget = game
+            .ability_menu
+            .map(|selected| self.render_ability_menu(&game.player.character, selected))
+            .or_else(|| {
+                game.current_message.as_ref().map(|message| {
+                    Paragraph::new(message.clone())
+                        .block(Block::default().borders(Borders::ALL).title("Message"))
+                        .wrap(Wrap { trim: true })
+                })
+            });
 
         //    D) Action widget (bottom box)
         let action_widget = {
-            let current_tile = game.world.get_tile(&game.player.world_pos);
-            let prompt = self.get_tile_actions(current_tile).unwrap_or_default();
+            let prompt = if let GamePhase::PlayingLocation(stack) = &game.phase {
+                self.get_location_actions(
+                    &game.keymap,
+                    stack.current(),
+                    &game.player.local_pos,
+                    stack.depth(),
+                    &interaction_labels(game),
+                )
+            } else {
+                let current_tile = game.world.get_tile(&game.player.world_pos);
+                let on_summit = game.world.is_summit(&game.player.world_pos);
+                self.get_tile_actions(&game.keymap, current_tile, on_summit, &interaction_labels(game)).unwrap_or_default()
+            };
             Paragraph::new(prompt)
                 .block(Block::default().borders(Borders::ALL).title("Actions"))
+                .wrap(Wrap { trim: true })
         };
 
         //    E) Location widget (for GamePhase::PlayingLocation)
         //       We only need to build it if we’re actually in that phase:
         let location_widget: Option<Paragraph<'static>> =
-            if let GamePhase::PlayingLocation(location_map) = &game.phase {
+            if let GamePhase::PlayingLocation(stack) = &game.phase {
+                let location_map = stack.current();
                 let loc_widget = self.render_location_map(
                     location_map,
                     &game.player.local_pos,
-                    "Location",
+                    &location_map.name,
+                    disposition_color(game.local_disposition()),
                 );
                 Some(loc_widget)
             } else {
@@ -160,14 +282,22 @@ impl TuiRenderer {
                     if let Some(loc_w) = &location_widget {
                         f.render_widget(loc_w.clone(), chunks[0]);
                     }
+                    f.render_widget(stats_widget.clone(), chunks[1]);
+                    if let Some(msg_w) = &message_widget {
+                        f.render_widget(msg_w.clone(), chunks[2]);
+                    }
                 }
-                GamePhase::Menu => {
-                    // TODO
-                }
-                GamePhase::GameOver => {
-                    // TODO
-                }
-                GamePhase::Map => {
+                GamePhase::Exit => {}
+                GamePhase::Map(_)
+                | GamePhase::Journal(_)
+                | GamePhase::Combat(_)
+                | GamePhase::Menu(_)
+                | GamePhase::CharacterSheet(_)
+                | GamePhase::Inventory(_)
+                | GamePhase::Feature(_)
+                | GamePhase::Trade(_)
+                | GamePhase::Reputation(_)
+                | GamePhase::GameOver(_) => {
                     // never reached here because we returned early
                 }
             }
@@ -177,7 +307,7 @@ impl TuiRenderer {
         Ok(())
     }
     pub fn get_tile_appearance(&self, tile: &Tile) -> char {
-        if let Some(location) = &tile.location {
+        if let Some(location) = tile.known_location() {
             return match location.species {
                 Species::Human => 'H',
                 Species::Orc => 'O',
@@ -205,64 +335,690 @@ impl TuiRenderer {
 
     pub fn render_full_map_widget<'a>(
         &self,
-        world: &crate::systems::world::World,
+        game: &Game,
+        view: &MapView,
         area: Rect,
     ) -> Paragraph<'a> {
-        use ratatui::text::Text;
-        use ratatui::text::Line;
-        use ratatui::text::Span;
+        let world = &game.world;
 
-        // How many “rows” of tiles we will draw
-        let rows = area.height as usize;
-        // How many “cols” of tiles: we print each tile as two characters ("X "),
-        // so we divide available width by 2.
-        let cols = (area.width as usize) / 2;
+        // Each cell is drawn as two characters ("X "), inside the border
+        let rows = area.height.saturating_sub(2) as i32;
+        let cols = (area.width.saturating_sub(2) / 2) as i32;
+        let scale = view.scale();
 
-        // If the terminal is too narrow (<2 columns), just return an empty box.
-        if rows == 0 || cols == 0 {
-            let empty = Paragraph::new(Text::from(vec![Line::from("")]))
-                .block(Block::default().borders(Borders::ALL).title("World Map"));
-            return empty;
+        let mut lines: Vec<Line> = Vec::with_capacity(rows as usize);
+        for row in 0..rows {
+            let mut spans: Vec<Span> = Vec::with_capacity(cols as usize);
+            for col in 0..cols {
+                let (dc, dr) = (col - cols / 2, row - rows / 2);
+                let span = match view.cell_origin(world, dc, dr) {
+                    Some(origin) => {
+                        let span = self.render_map_cell(game, &origin, scale);
+                        if dc == 0 && dr == 0 {
+                            span.reversed()
+                        } else {
+                            span
+                        }
+                    }
+                    None => Span::raw("  "),
+                };
+                spans.push(span);
+            }
+            lines.push(Line::from(spans));
         }
 
-        let world_w = world.width as usize;
-        let world_h = world.height as usize;
+        let title = format!(
+            "World Map ({}×{}) - 1:{} - [{}] Relief {}",
+            world.width, world.height, scale,
+            game.keymap.key_label(KeyContext::Map, Command::ToggleRelief),
+            if game.map_relief { "on" } else { "off" }
+        );
 
-        let mut lines: Vec<Line> = Vec::with_capacity(rows);
+        Paragraph::new(Text::from(lines))
+            .block(Block::default().borders(Borders::ALL).title(title))
+    }
 
-        for row in 0..rows {
-            // Which world‐row does this terminal row correspond to?
-            // Use integer scaling: world_y = row * world_h / rows
-            let world_y = (row * world_h) / rows;
+    // Draw one map cell covering `scale`×`scale` world tiles. Only tiles the
+    // player has seen count: markers win over landmarks, landmarks win over
+    // terrain, and otherwise the most common terrain in the cell is shown.
+    fn render_map_cell(&self, game: &Game, origin: &Position, scale: usize) -> Span<'static> {
+        let world = &game.world;
+        let mut terrain_counts: Vec<(TerrainType, usize, Position)> = Vec::new();
+        let mut landmark: Option<Position> = None;
+        let mut marker = false;
+
+        for dy in 0..scale as i32 {
+            for dx in 0..scale as i32 {
+                let Some(pos) = world.neighbour(origin, dx, dy) else {
+                    continue;
+                };
+                if pos == game.player.world_pos {
+                    return Span::styled("@ ", Style::default().bold());
+                }
+                if game.journal.marker_at(&pos).is_some() {
+                    marker = true;
+                }
+                let tile = world.get_tile(&pos);
+                if !tile.seen {
+                    continue;
+                }
+                if tile.known_location().is_some() {
+                    landmark.get_or_insert(pos);
+                }
+                match terrain_counts.iter_mut().find(|(terrain, _, _)| *terrain == tile.terrain) {
+                    Some(entry) => entry.1 += 1,
+                    None => terrain_counts.push((tile.terrain, 1, pos)),
+                }
+            }
+        }
 
-            let mut spans: Vec<Span> = Vec::with_capacity(cols);
+        // Player markers are shown even on unexplored land
+        if marker {
+            return Span::styled("! ", Style::default().fg(Color::Magenta).bold());
+        }
 
-            for col in 0..cols {
-                // Which world‐col does this terminal column correspond to?
-                let world_x = (col * world_w) / cols;
+        let shown = landmark.or_else(|| {
+            terrain_counts
+                .iter()
+                .max_by_key(|(_, count, _)| *count)
+                .map(|(_, _, pos)| *pos)
+        });
+        let Some(pos) = shown else {
+            // Nothing seen here yet
+            return Span::raw("  ");
+        };
+
+        let tile = world.get_tile(&pos);
+        let style = if game.map_relief && tile.known_location().is_none() {
+            self.get_relief_style(world, &pos)
+        } else {
+            self.get_terrain_style(tile)
+        };
+        Span::styled(format!("{} ", self.get_tile_appearance(tile)), style)
+    }
+
+    // What the map cursor is pointing at
+    fn render_map_info(&self, game: &Game, view: &MapView) -> Paragraph<'static> {
+        let cursor = view.cursor;
+        let tile = game.world.get_tile(&cursor);
+
+        if let Some(note) = &view.note {
+            let lines = vec![
+                Line::from(format!("Note for ({}, {}): {}_", cursor.x, cursor.y, note)),
+                Line::from("[Enter] Save marker | [Esc] Cancel"),
+            ];
+            return Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL).title("New Marker"))
+                .wrap(Wrap { trim: true });
+        }
+
+        let key = |command| game.keymap.key_label(KeyContext::Map, command);
+        let mut lines = vec![Line::from(format!(
+            "Cursor ({}, {}) | [{}/{}] Zoom | [{}] Centre | [{}] Travel here | [{}] Note | [{}] Delete note | [{}] Relief | [{}] Close",
+            cursor.x, cursor.y,
+            key(Command::ZoomIn),
+            key(Command::ZoomOut),
+            key(Command::CentreMap),
+            key(Command::Confirm),
+            key(Command::AddNote),
+            key(Command::DeleteNote),
+            key(Command::ToggleRelief),
+            key(Command::Cancel),
+        ))];
+        if let Some(message) = &game.current_message {
+            lines.push(Line::from(message.clone()));
+        }
+        if let Some(marker) = game.journal.marker_at(&cursor) {
+            lines.push(Line::from(Span::styled(
+                format!("Marker: {}", marker.note),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+        if tile.seen {
+            lines.push(Line::from(format!(
+                "{:?}, elevation {}m",
+                tile.terrain,
+                tile.elevation_meters()
+            )));
+            if let Some(location) = tile.known_location() {
+                lines.push(Line::from(format!(
+                    "{}: {}",
+                    location.name,
+                    location.generate_description()
+                )));
+            }
+        } else {
+            lines.push(Line::from("Unexplored"));
+        }
+
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Map Info"))
+            .wrap(Wrap { trim: true })
+    }
+
+    // The journal screen: the list of places or markers, and details of the selected one
+    fn render_journal(&self, game: &Game, view: &JournalView, area: Rect) -> (Paragraph<'static>, Paragraph<'static>) {
+        let player_pos = game.player.world_pos;
+        let distance = |pos: &Position| game.world.distance(&player_pos, pos);
+        let highlight = |line: Line<'static>, index: usize| {
+            if index == view.selected { line.reversed() } else { line }
+        };
+
+        let (rows, details): (Vec<Line>, Vec<Line>) = match view.tab {
+            JournalTab::Places => {
+                let places = game.journal.sorted_places(view.sort, distance);
+                let rows = places.iter().enumerate().map(|(i, entry)| {
+                    highlight(Line::from(format!(
+                        "{:>3}. {:<20} {:<6} {:<10} ({}, {}) {} tiles away",
+                        entry.discovered,
+                        entry.name,
+                        entry.species.to_string(),
+                        entry.state.to_string(),
+                        entry.position.x,
+                        entry.position.y,
+                        distance(&entry.position)
+                    )), i)
+                }).collect();
+                let details = places.get(view.selected).map(|entry| vec![
+                    Line::from(entry.name.clone()).bold(),
+                    Line::from(entry.description.clone()),
+                ]).unwrap_or_else(|| vec![Line::from("No places discovered yet.")]);
+                (rows, details)
+            }
+            JournalTab::Markers => {
+                let markers = game.journal.sorted_markers(view.sort, distance);
+                let rows = markers.iter().enumerate().map(|(i, marker)| {
+                    highlight(Line::from(format!(
+                        "({}, {}) {} tiles away: {}",
+                        marker.position.x,
+                        marker.position.y,
+                        distance(&marker.position),
+                        marker.note
+                    )), i)
+                }).collect();
+                let details = markers.get(view.selected).map(|marker| vec![
+                    Line::from(marker.note.clone()),
+                ]).unwrap_or_else(|| vec![Line::from(format!(
+                    "No markers yet. Press [{}] on the world map to add one.",
+                    game.keymap.key_label(KeyContext::Map, Command::AddNote)
+                ))]);
+                (rows, details)
+            }
+        };
+
+        let tab_name = match view.tab {
+            JournalTab::Places => "Places",
+            JournalTab::Markers => "Markers",
+        };
+        let title = format!("Journal - {} - sorted by {}", tab_name, view.sort.label());
+        // Scroll so the selected row stays on screen
+        let visible_rows = area.height.saturating_sub(2).max(1) as usize;
+        let scroll = view.selected.saturating_sub(visible_rows - 1) as u16;
+        let list_widget = Paragraph::new(rows)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((scroll, 0));
+
+        let mut details = details;
+        let key = |command| game.keymap.key_label(KeyContext::Journal, command);
+        details.push(Line::from(format!(
+            "[{}] Places/Markers | [{}] Sort | [{}/{}] Select | [{}] Show on map | [{}] Close",
+            key(Command::NextTab),
+            key(Command::CycleSort),
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Confirm),
+            key(Command::Cancel),
+        )));
+        let detail_widget = Paragraph::new(details)
+            .block(Block::default().borders(Borders::ALL).title("Details"))
+            .wrap(Wrap { trim: true });
+
+        (list_widget, detail_widget)
+    }
 
-                // Grab wrapped coordinates (in case your world is toroidal)
-                let pos = crate::systems::position::Position {
-                    x: world_x,
-                    y: world_y,
+    fn render_menu(&self, game: &Game, view: &MenuView) -> Paragraph<'static> {
+        let highlight = |line: Line<'static>, index: usize| {
+            if index == view.selected { line.reversed() } else { line }
+        };
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        let up_down = format!(
+            "{}/{}",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South))
+        );
+        let left_right = format!(
+            "{}/{}",
+            key(Command::Move(command::Direction::West)),
+            key(Command::Move(command::Direction::East))
+        );
+
+        let mut lines = vec![Line::from(""), Line::from("A CONSOLE RPG").bold(), Line::from("")];
+        let (title, help) = match view.screen {
+            MenuScreen::Title => {
+                for (i, item) in TITLE_ITEMS.iter().enumerate() {
+                    lines.push(highlight(Line::from(format!(" {} ", item.label())), i));
+                }
+                ("Main Menu", format!("[{}] Select | [{}] Choose", up_down, key(Command::Confirm)))
+            }
+            MenuScreen::NewGame => {
+                for (i, field) in NEW_GAME_FIELDS.iter().enumerate() {
+                    let text = match field {
+                        NewGameField::Seed => format!(" Seed: {}_ ", view.seed),
+                        NewGameField::Size => {
+                            let (name, width, height) = WORLD_SIZES[view.world_size];
+                            format!(" World size: < {} ({}×{}) > ", name, width, height)
+                        }
+                        NewGameField::Start => " Create a character ".to_string(),
+                    };
+                    lines.push(highlight(Line::from(text), i));
+                }
+                ("New Game", format!(
+                    "Type digits for the seed | [{}] Change size | [{}] Continue | [{}] Back",
+                    left_right, key(Command::Confirm), key(Command::Cancel)
+                ))
+            }
+            MenuScreen::Character => {
+                let draft = &view.draft;
+                for (i, field) in CHARACTER_FIELDS.iter().enumerate() {
+                    let text = match field {
+                        CharacterField::Name => format!(" Name: {}_ ", draft.name),
+                        CharacterField::Species => format!(" Species: < {} > ", draft.species()),
+                        CharacterField::Class => format!(" Class: < {} > ", draft.class().name),
+                        CharacterField::Method if draft.rolled => " Attributes: < Rolled > ".to_string(),
+                        CharacterField::Method => format!(
+                            " Attributes: < Point buy, {} points left > ",
+                            draft.attributes.points_left()
+                        ),
+                        CharacterField::Attribute(attribute) => {
+                            format!(" {}: {} ", attribute.label(), draft.attributes.get(*attribute))
+                        }
+                        CharacterField::Begin => " Begin the adventure ".to_string(),
+                    };
+                    lines.push(highlight(Line::from(text), i));
+                }
+                // Derived values as they will be in play
+                let character = draft.character();
+                lines.push(Line::from(""));
+                lines.push(Line::from(format!(
+                    "Health: {} | Attack roll: {} | Dodge roll: {} | Spell power: {}",
+                    character.max_health(),
+                    character.attack(),
+                    character.dodge(),
+                    character.spell_power()
+                )));
+                let traits = draft.species().traits();
+                lines.push(Line::from(format!("{} ({})", traits.description, traits.stats.describe())));
+                let class = draft.class();
+                let abilities: Vec<&str> = class.abilities.iter().map(|ability| ability.name.as_str()).collect();
+                lines.push(Line::from(format!(
+                    "{} ({}) Abilities: {}. Starts with: {}.",
+                    class.description,
+                    class.stats.describe(),
+                    abilities.join(", "),
+                    class.equipment.join(", ")
+                )));
+                lines.push(Line::from(format!(
+                    "You will start in the {} settlement nearest the heart of the world.",
+                    draft.species()
+                )));
+                let reroll = if draft.rolled {
+                    format!(" ([{}] on Attributes re-rolls)", key(Command::Confirm))
+                } else {
+                    String::new()
                 };
-                let wrapped = world.get_wrapped_coordinates(&pos);
-                let tile = world.get_tile(&wrapped);
+                ("New Character", format!(
+                    "Type the name | [{}] Select | [{}] Change | [{}] Begin{} | [{}] Back",
+                    up_down, left_right, key(Command::Confirm), reroll, key(Command::Cancel)
+                ))
+            }
+            MenuScreen::Settings => {
+                let preset = format!("{:?}", KEY_PRESETS[view.key_preset]);
+                lines.push(highlight(Line::from(format!(" Key bindings: < {} > ", preset)), 0));
+                lines.push(Line::from(""));
+                lines.push(Line::from("Changes last until you quit; keys.toml is read at startup."));
+                ("Settings", format!("[{}] Change | [{}] Back", left_right, key(Command::Cancel)))
+            }
+        };
+        lines.push(Line::from(""));
+        if let Some(notice) = &view.notice {
+            lines.push(Line::from(notice.clone()).yellow());
+        }
+        lines.push(Line::from(help));
 
-                // Draw each tile as "<symbol><space>"
-                let symbol = self.get_tile_appearance(tile);
-                let style = self.get_terrain_style(tile);
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+    }
+
+    fn render_combat(
+        &self,
+        game: &Game,
+        combat: &Combat,
+        actions: &[CombatAction],
+    ) -> (Paragraph<'static>, Paragraph<'static>) {
+        let character = &game.player.character;
+        let enemy = &combat.enemy;
+        let mut lines = vec![
+            Line::from(format!("{}: {}/{} HP", enemy.name, enemy.health.max(0), enemy.max_health)).bold(),
+            Line::from(format!("{}: {}/{} HP", character.name, character.health, character.max_health())),
+        ];
+        if let Some(class) = character.class_def() {
+            lines.push(Line::from(format!("{}: {}/{}", class.resource, character.resource, character.max_resource)));
+        }
+        lines.push(Line::from(""));
+        lines.extend(combat.log.iter().map(|line| Line::from(line.clone())));
+        let fight_widget = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(format!("Fighting a {}", enemy.name)))
+            .wrap(Wrap { trim: true });
+
+        let mut rows: Vec<Line> = actions.iter().enumerate().map(|(i, action)| {
+            let text = match action {
+                CombatAction::Attack => "Attack".to_string(),
+                CombatAction::Ability(index) => ability_label(character, *index),
+                CombatAction::Flee => "Flee".to_string(),
+            };
+            let line = Line::from(format!(" {} ", text));
+            if i == combat.selected { line.reversed() } else { line }
+        }).collect();
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        rows.push(Line::from(format!(
+            "[{}/{}] Select | [{}] Act",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Confirm),
+        )));
+        let action_widget = Paragraph::new(rows)
+            .block(Block::default().borders(Borders::ALL).title("Your turn"));
+        (fight_widget, action_widget)
+    }
+
+    fn render_ability_menu(&self, character: &Character, selected: usize) -> Paragraph<'static> {
+        let lines: Vec<Line> = (0..character.abilities().len()).map(|index| {
+            let line = Line::from(format!(" {} ", ability_label(character, index)));
+            if index == selected { line.reversed() } else { line }
+        }).collect();
+        let title = match character.class_def() {
+            Some(class) => format!("Abilities - {} {}/{}", class.resource, character.resource, character.max_resource),
+            None => "Abilities".to_string(),
+        };
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title))
+    }
+
+    fn render_character_sheet(&self, game: &Game, sheet: &CharacterSheet) -> Paragraph<'static> {
+        let character = &game.player.character;
+        let (progress, needed) = character.level_progress();
+        let filled = (progress * XP_BAR_WIDTH / needed.max(1)).clamp(0, XP_BAR_WIDTH) as usize;
+        let mut lines = vec![
+            Line::from(format!("{}, {} {}", character.name, character.species, character.class)).bold(),
+            Line::from(""),
+            Line::from(format!("Level {}", character.level)),
+            Line::from(format!(
+                "[{}{}] {}/{} XP to level {}",
+                "#".repeat(filled),
+                "-".repeat(XP_BAR_WIDTH as usize - filled),
+                progress,
+                needed,
+                character.level + 1
+            )),
+            Line::from(format!("Points to spend: {}", character.unspent_points)),
+            Line::from(""),
+        ];
+        let modifiers = character.modifiers();
+        let mut header = format!("{:<13}{:>5}", "", "Base");
+        for (source, _) in &modifiers {
+            header.push_str(&format!("{:>10}", source));
+        }
+        header.push_str(&format!("{:>7}", "Total"));
+        lines.push(Line::from(header).bold());
+        for (i, attribute) in ATTRIBUTES.iter().enumerate() {
+            let mut row = format!("{:<13}{:>5}", attribute.label(), character.attributes.get(*attribute));
+            for (_, stats) in &modifiers {
+                row.push_str(&format!("{:>10}", signed(stats.get(*attribute))));
+            }
+            row.push_str(&format!("{:>7}", character.attribute(*attribute)));
+            let line = Line::from(row);
+            lines.push(if i == sheet.selected { line.reversed() } else { line });
+        }
+        let mut health_row = format!("{:<13}{:>5}", "Health", "");
+        for (_, stats) in &modifiers {
+            health_row.push_str(&format!("{:>10}", signed(stats.health)));
+        }
+        health_row.push_str(&format!("{:>7}", ""));
+        lines.push(Line::from(health_row));
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "Health: {}/{} | Attack: {} | Dodge: {} | Spell power: {}",
+            character.health,
+            character.max_health(),
+            character.attack(),
+            character.dodge(),
+            character.spell_power()
+        )));
+        lines.push(Line::from("Health = Con × 10 + Health | Attack = Str + Luck/2 | Dodge = Dex + Luck/2 | Spell power = Int/2"));
+        lines.push(Line::from(""));
+        for effect in &character.effects {
+            let description = effect.def().map_or("", |def| def.description.as_str());
+            lines.push(Line::from(format!("{}: {}", effect.describe(), description)));
+        }
+        if !character.effects.is_empty() {
+            lines.push(Line::from(""));
+        }
+        if let Some(message) = &game.current_message {
+            lines.push(Line::from(message.clone()).yellow());
+        }
+        let key = |command| game.keymap.key_label(KeyContext::Character, command);
+        lines.push(Line::from(format!(
+            "[{}/{}] Select | [{}] Spend a point | [{}] Close",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Confirm),
+            key(Command::Cancel),
+        )));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Character"))
+            .alignment(Alignment::Center)
+    }
 
-                spans.push(Span::styled(format!("{} ", symbol), style));
+    fn render_inventory(&self, game: &Game, view: &InventoryView) -> Paragraph<'static> {
+        let character = &game.player.character;
+        let highlight = |line: Line<'static>, row: usize| if row == view.selected { line.reversed() } else { line };
+        let describe = |item: &Item| match item.slot {
+            Some(_) if item.is_broken() => format!("{} (broken)", item.name),
+            Some(_) if item.condition < MAX_CONDITION => {
+                format!("{} ({}, {}% condition)", item.name, item.stats.describe(), item.condition)
             }
+            Some(_) => format!("{} ({})", item.name, item.stats.describe()),
+            None => item.name.clone(),
+        };
 
-            lines.push(Line::from(spans));
+        let mut lines = vec![Line::from("Worn").bold()];
+        for (row, slot) in EQUIP_SLOTS.iter().enumerate() {
+            let worn = character.equipment.get(*slot).map_or("-".to_string(), describe);
+            lines.push(highlight(Line::from(format!(" {:<11}{} ", slot.label(), worn)), row));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("Pack").bold());
+        if character.inventory.is_empty() {
+            lines.push(Line::from(" Your pack is empty."));
+        }
+        for (index, item) in character.inventory.iter().enumerate() {
+            lines.push(highlight(Line::from(format!(" {} ", describe(item))), EQUIP_SLOTS.len() + index));
         }
 
-        let title = format!("World Map ({}×{})", world_w, world_h);
+        lines.push(Line::from(""));
+        let selected = match view.row() {
+            InventoryRow::Worn(slot) => character.equipment.get(slot),
+            InventoryRow::Pack(index) => character.inventory.get(index),
+        };
+        if let Some(item) = selected {
+            lines.push(Line::from(item.description().to_string()));
+        }
+        lines.push(Line::from(format!(
+            "Health: {}/{} | Attack: {} | Dodge: {}",
+            character.health,
+            character.max_health(),
+            character.attack(),
+            character.dodge()
+        )));
+        if let Some(message) = &game.current_message {
+            lines.push(Line::from(message.clone()).yellow());
+        }
+        let key = |command| game.keymap.key_label(KeyContext::Inventory, command);
+        let action = match view.row() {
+            InventoryRow::Worn(_) => "Take off",
+            InventoryRow::Pack(_) => "Wear",
+        };
+        lines.push(Line::from(format!(
+            "[{}/{}] Select | [{}] {} | [{}] Close",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Confirm),
+            action,
+            key(Command::Cancel),
+        )));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Inventory"))
+            .wrap(Wrap { trim: false })
+    }
 
-        Paragraph::new(Text::from(lines))
-            .block(Block::default().borders(Borders::ALL).title(title))
+    fn render_feature(&self, game: &Game, view: &FeatureView) -> Paragraph<'static> {
+        let character = &game.player.character;
+        let mut lines = vec![
+            Line::from(format!("Gold: {} | Health: {}/{}", game.player.gold, character.health, character.max_health())),
+            Line::from(""),
+        ];
+        for (index, offer) in game.feature_offers(view.feature.feature_type).iter().enumerate() {
+            let price = if offer.price > 0 { format!("{:>5} gold", offer.price) } else { String::new() };
+            let line = Line::from(format!(" {:<44}{:>10} ", offer.label, price));
+            lines.push(if index == view.selected { line.reversed() } else { line });
+        }
+        lines.push(Line::from(""));
+        if let Some(message) = &game.current_message {
+            lines.push(Line::from(message.clone()).yellow());
+        }
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        lines.push(Line::from(format!(
+            "[{}/{}] Select | [{}] Accept | [{}] Leave",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Confirm),
+            key(Command::Cancel),
+        )));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(view.feature.name.clone()))
+            .wrap(Wrap { trim: false })
+    }
+
+    fn render_trade(&self, game: &Game, view: &TradeView) -> Paragraph<'static> {
+        let (buying, selling) = match view.side {
+            TradeSide::Buy => (Span::from(" Buy ").reversed(), Span::from(" Sell ")),
+            TradeSide::Sell => (Span::from(" Buy "), Span::from(" Sell ").reversed()),
+        };
+        let mut lines = vec![
+            Line::from(format!("Gold: {}", game.player.gold)),
+            Line::from(vec![buying, Span::from(" | "), selling]),
+            Line::from(""),
+        ];
+        let rows: Vec<(Item, i32)> = match view.side {
+            TradeSide::Buy => view
+                .market
+                .stock(&view.wares)
+                .into_iter()
+                .map(|item| {
+                    let price = view.market.price(&item, &game.clock);
+                    (item, price)
+                })
+                .collect(),
+            TradeSide::Sell => game
+                .player
+                .character
+                .inventory
+                .iter()
+                .map(|item| (item.clone(), view.market.offer(item, &game.clock)))
+                .collect(),
+        };
+        if rows.is_empty() {
+            lines.push(Line::from(" Nothing to trade."));
+        }
+        for (index, (item, price)) in rows.iter().enumerate() {
+            let price = if *price > 0 { format!("{:>5} gold", price) } else { "no use".to_string() };
+            let line = Line::from(format!(" {:<30}{:>10} {:<6}", item.name, price, price_note(view, item, game)));
+            lines.push(if index == view.selected { line.reversed() } else { line });
+        }
+        lines.push(Line::from(""));
+        if let Some(message) = &game.current_message {
+            lines.push(Line::from(message.clone()).yellow());
+        }
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        let action = match view.side {
+            TradeSide::Buy => "Buy",
+            TradeSide::Sell => "Sell",
+        };
+        lines.push(Line::from(format!(
+            "[{}/{}] Select | [{}/{}] Buy or sell | [{}] {} | [{}] Leave",
+            key(Command::Move(command::Direction::North)),
+            key(Command::Move(command::Direction::South)),
+            key(Command::Move(command::Direction::West)),
+            key(Command::Move(command::Direction::East)),
+            key(Command::Confirm),
+            action,
+            key(Command::Cancel),
+        )));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(format!("Trading in {}", view.name)))
+            .wrap(Wrap { trim: false })
+    }
+
+    fn render_reputation(&self, game: &Game) -> Paragraph<'static> {
+        let factions = game.known_factions();
+        let mut lines = vec![Line::from(format!("{:<28}{:>9}", "Faction", "Standing")).bold()];
+        if factions.is_empty() {
+            lines.push(Line::from(" You have not met anyone yet."));
+        }
+        for (faction, value) in factions {
+            let standing = Standing::from_value(value);
+            let color = match standing {
+                Standing::Hated => Color::Red,
+                Standing::Disliked => Color::Yellow,
+                Standing::Neutral => Color::Reset,
+                Standing::Liked => Color::Green,
+                Standing::Honoured => Color::Cyan,
+            };
+            lines.push(Line::from(vec![
+                Span::from(format!("{:<28}{:>9}  ", faction.to_string(), value)),
+                Span::styled(standing.label().to_string(), Style::default().fg(color)),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("Good deeds and fair dealing raise your standing; kin under other rule hear of it too."));
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        lines.push(Line::from(format!("[{}] Back", key(Command::Cancel))));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Reputation"))
+            .wrap(Wrap { trim: false })
+    }
+
+    fn render_game_over(&self, game: &Game, summary: &GameSummary) -> Paragraph<'static> {
+        let key = |command| game.keymap.key_label(KeyContext::Global, command);
+        let lines = vec![
+            Line::from(""),
+            Line::from(format!("The adventures of {} are over.", summary.name)).bold(),
+            Line::from(""),
+            Line::from(format!("Days survived: {}", summary.days)),
+            Line::from(format!("Places discovered: {}", summary.places)),
+            Line::from(format!("Experience: {} XP, level {}", summary.xp, summary.level)),
+            Line::from(format!("Cause: {}", summary.cause)),
+            Line::from(""),
+            Line::from(format!(
+                "[{}] Back to the main menu | [{}] Quit",
+                key(Command::Confirm),
+                key(Command::Cancel)
+            )),
+        ];
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Game Over"))
+            .alignment(Alignment::Center)
     }
 
     fn create_layout(&self, size: Rect) -> Vec<Rect> {
@@ -301,7 +1057,7 @@ impl TuiRenderer {
         game: &Game,
         area: Rect,
     ) {
-        let map = self.render_tile_map(&game.world, &game.player.world_pos, game.view_radius, "The World");
+        let map = self.render_tile_map(&game.world, &game.journal, &game.player.world_pos, game.view_radius, "The World");
         f.render_widget(map, area);
     }
 
@@ -329,7 +1085,8 @@ impl TuiRenderer {
         area: Rect,
     ) {
         let current_tile = game.world.get_tile(&game.player.world_pos);
-        if let Some(prompt) = self.get_tile_actions(current_tile) {
+        let on_summit = game.world.is_summit(&game.player.world_pos);
+        if let Some(prompt) = self.get_tile_actions(&game.keymap, current_tile, on_summit, &interaction_labels(game)) {
             let action_widget = Paragraph::new(prompt)
                 .block(Block::default()
                     .borders(Borders::ALL)
@@ -338,22 +1095,85 @@ impl TuiRenderer {
         }
     }
 
-    fn get_tile_actions(&self, tile: &Tile) -> Option<String> {
-        let base_actions = "[C] Camp | [I] Inventory | [Q] Quit";
-        
-        let tile_action = if let Some(location) = &tile.location {
-            format!("| [E] Enter {} Settlement", location.species)
+    fn get_tile_actions(
+        &self,
+        keymap: &Keymap,
+        tile: &Tile,
+        on_summit: bool,
+        interactions: &[(Interaction, String)],
+    ) -> Option<String> {
+        let key = |command| keymap.key_label(KeyContext::World, command);
+        let base_actions = format!(
+            "[{}] Travel to settlement | [{}] Explore | [{}] Map | [{}] Journal | [{}] Camp | [{}] Inventory | [{}] Abilities | [{}] Character | [{}] Reputation | [{}] Quit",
+            key(Command::TravelToSettlement),
+            key(Command::Explore),
+            key(Command::OpenMap),
+            key(Command::Journal),
+            key(Command::Camp),
+            key(Command::Inventory),
+            key(Command::Abilities),
+            key(Command::CharacterSheet),
+            key(Command::Reputation),
+            key(Command::Quit),
+        );
+
+        let mut tile_action = if let Some(location) = tile.known_location() {
+            format!("| [{}] Enter {} Settlement", key(Command::Enter), location.species)
         } else {
             String::new()
         };
+        if on_summit {
+            tile_action.push_str(&format!("| [{}] Survey from the summit ", key(Command::Survey)));
+        }
+        for (interaction, label) in interactions {
+            tile_action.push_str(&format!("| [{}] {} ", key(Command::Interact(*interaction)), label));
+        }
 
         Some(format!("{} {}", tile_action, base_actions))
     }
 
+    fn get_location_actions(
+        &self,
+        keymap: &Keymap,
+        map: &LocationMap,
+        player_pos: &Position,
+        depth: usize,
+        interactions: &[(Interaction, String)],
+    ) -> String {
+        let key = |command| keymap.key_label(KeyContext::Location, command);
+        let mut actions = Vec::new();
+
+        let tile = &map.tiles[player_pos.y][player_pos.x];
+        if let Some(kind) = BuildingKind::for_tile(tile) {
+            actions.push(format!("[{}] Enter {}", key(Command::Enter), kind.name()));
+        }
+        actions.push("[Walk into a building] Enter".to_string());
+        match tile.tile_type {
+            LocationTileType::StairsUp => actions.push(format!("[{}] Climb up", key(Command::StairsUp))),
+            LocationTileType::StairsDown => actions.push(format!("[{}] Go down", key(Command::StairsDown))),
+            _ => {}
+        }
+        for (interaction, label) in interactions {
+            actions.push(format!("[{}] {}", key(Command::Interact(*interaction)), label));
+        }
+        if depth > 1 {
+            actions.push(format!("[{}] Step outside", key(Command::Exit)));
+        } else {
+            actions.push(format!("[{}] Leave", key(Command::Exit)));
+        }
+        actions.push(format!("[{}] Abilities", key(Command::Abilities)));
+        actions.push(format!("[{}] Character", key(Command::CharacterSheet)));
+        actions.push(format!("[{}] Reputation", key(Command::Reputation)));
+        actions.push(format!("[{}] Quit", key(Command::Quit)));
+
+        actions.join(" | ")
+    }
+
     // render world map
     pub fn render_tile_map(
         &self,
         world: &World,
+        journal: &Journal,
         player_pos: &Position,
         view_radius: i32,
         title: &str,
@@ -377,7 +1197,6 @@ impl TuiRenderer {
                 let wrapped_pos = world.get_wrapped_coordinates(&current_pos);
                 let tile = world.get_tile(&wrapped_pos);
                 let symbol = self.get_tile_appearance(tile);
-                let dist = dx*dx + dy*dy;
                 
                 // fog of war
                 let span = if !tile.seen {
@@ -386,14 +1205,17 @@ impl TuiRenderer {
                 } else if dx == 0 && dy == 0 {
                     // Player position - bold white
                     Span::styled("@ ", Style::default().bold())
-                } else if dist <= FOG_RADIUS*FOG_RADIUS {
-                    // In view range - colored by terrain/feature
+                } else if journal.marker_at(&wrapped_pos).is_some() {
+                    // Player marker - magenta
+                    Span::styled("! ", Style::default().fg(Color::Magenta).bold())
+                } else if world.is_visible(&wrapped_pos) {
+                    // In line of sight - colored by terrain/feature
                     Span::styled(
                         format!("{} ", symbol),
                         self.get_terrain_style(tile)
                     )
                 } else {
-                    // Out of view range but seen - dimmed
+                    // Out of sight but remembered - dimmed
                     Span::styled(
                         format!("{} ", symbol),
                         self.get_terrain_style(tile).dim()
@@ -416,7 +1238,7 @@ impl TuiRenderer {
     }
 
     fn get_terrain_style(&self,tile: &Tile) -> Style {
-        if let Some(location) = &tile.location {
+        if let Some(location) = tile.known_location() {
             return match location.species {
                 Species::Human => Style::default().fg(Color::White),
                 Species::Orc => Style::default().fg(Color::Red),
@@ -445,34 +1267,74 @@ impl TuiRenderer {
         Style::default().fg(color)
     }
 
-    // Add a new function to render the stats widget
-    fn render_stats_widget(
-        &self,
-        f: &mut Frame<'_>,
-        game: &Game,
-        area: Rect,
-    ) {
-        let stats = vec![
-            format!("Name: {}", game.player.character.name),
-            format!("Species: {}", game.player.character.species),
-            format!("Class: {}", game.player.character.class),
-            format!("HP: {}/{}", game.player.character.health, game.player.character.max_health),
-            format!("Str: {}", game.player.character.attack),
-            format!("Int: {}", game.player.character.dodge),
-            format!("Dex: {}", game.player.character.luck),
-        ];
+    // Terrain colour shaded by elevation, lit from the north-west
+    fn get_relief_style(&self, world: &World, pos: &Position) -> Style {
+        let tile = world.get_tile(pos);
+        let (r, g, b) = match tile.terrain {
+            TerrainType::Water => (30, 60, 200),
+            TerrainType::Plains => (90, 190, 70),
+            TerrainType::Forest => (30, 130, 40),
+            TerrainType::Desert => (210, 200, 120),
+            TerrainType::Jungle => (20, 110, 30),
+            TerrainType::Snow => (235, 235, 245),
+            TerrainType::Swamp => (60, 100, 60),
+            TerrainType::Mountains => (140, 130, 120),
+            TerrainType::Road => (160, 150, 130),
+        };
 
-        // Convert Vec<String> to Vec<Line>
-        let lines: Vec<Line> = stats.iter()
-            .map(|s| Line::from(s.as_str()))
-            .collect();
+        let north_west = world.get_wrapped_coordinates(&Position {
+            x: pos.x + world.width - 1,
+            y: pos.y + world.height - 1,
+        });
+        let slope = tile.height - world.get_tile(&north_west).height;
+        let shade = (0.8 + slope * 12.0 + (tile.height - 0.5) * 0.8).clamp(0.35, 1.3);
+        let apply = |channel: u8| (channel as f32 * shade).min(255.0) as u8;
 
-        let stats_widget = Paragraph::new(lines)
-            .block(Block::default()
-                .borders(Borders::ALL)
-                .title("Character"));
+        Style::default().fg(Color::Rgb(apply(r), apply(g), apply(b)))
+    }
 
-        f.render_widget(stats_widget, area);
+    fn get_location_tile_appearance(&self, tile: &LocationTile) -> (&'static str, Style) {
+        if let Some(feature) = &tile.feature {
+            // Features
+            let symbol = match feature.feature_type {
+                FeatureType::Market => "M ",
+                FeatureType::Temple => "T ",
+                FeatureType::Tavern => "A ",
+                FeatureType::Blacksmith => "B ",
+                FeatureType::Garden => "* ",
+                FeatureType::TrainingGround => "X ",
+                FeatureType::Storage => "S ",
+            };
+            (symbol, Style::default().fg(Color::Yellow))
+        } else {
+            // Regular tiles
+            match tile.tile_type {
+                LocationTileType::Ground => (". ", Style::default().fg(Color::DarkGray)),
+                LocationTileType::Wall => ("# ", Style::default().fg(Color::White)),
+                LocationTileType::Water => ("~ ", Style::default().fg(Color::Blue)),
+                LocationTileType::HumanRoad => ("= ", Style::default().fg(Color::Gray)),
+                LocationTileType::ElfPath => ("- ", Style::default().fg(Color::Green)),
+                LocationTileType::OrcTrail => (": ", Style::default().fg(Color::Red)),
+                LocationTileType::HumanHouse => ("H ", Style::default().fg(Color::White)),
+                LocationTileType::ElfTreehouse => ("T ", Style::default().fg(Color::Green)),
+                LocationTileType::OrcHut => ("O ", Style::default().fg(Color::Red)),
+                LocationTileType::Trading => ("$ ", Style::default().fg(Color::Yellow)),
+                LocationTileType::Shrine => ("^ ", Style::default().fg(Color::Magenta)),
+                LocationTileType::Floor => (". ", Style::default().fg(Color::Gray)),
+                LocationTileType::Door => ("+ ", Style::default().fg(Color::LightYellow)),
+                LocationTileType::Table => ("o ", Style::default().fg(Color::Yellow)),
+                LocationTileType::Bench => ("- ", Style::default().fg(Color::Yellow)),
+                LocationTileType::Counter => ("= ", Style::default().fg(Color::Yellow)),
+                LocationTileType::Bed => ("b ", Style::default().fg(Color::LightBlue)),
+                LocationTileType::Hearth => ("% ", Style::default().fg(Color::LightRed)),
+                LocationTileType::Altar => ("^ ", Style::default().fg(Color::Magenta)),
+                LocationTileType::Anvil => ("π ", Style::default().fg(Color::Gray)),
+                LocationTileType::Shelf => ("[ ", Style::default().fg(Color::Yellow)),
+                LocationTileType::StairsUp => ("< ", Style::default().fg(Color::White).bold()),
+                LocationTileType::StairsDown => ("> ", Style::default().fg(Color::White).bold()),
+                LocationTileType::Foliage => ("\" ", Style::default().fg(Color::Green)),
+            }
+        }
     }
 
     fn render_location_map(
@@ -480,6 +1342,7 @@ impl TuiRenderer {
         map: &LocationMap,
         player_pos: &Position,
         title: &str,
+        npc_color: Color,
     ) -> Paragraph<'static> {
         let mut lines = Vec::new();
         let (px, py) = (player_pos.x as i32, player_pos.y as i32);
@@ -487,15 +1350,11 @@ impl TuiRenderer {
         for dy in -RENDER_RADIUS..=RENDER_RADIUS {
             let mut row = Vec::new();
             for dx in -RENDER_RADIUS..=RENDER_RADIUS {
-                let dist = dx*dx + dy*dy;
                 let world_x = px + dx;
                 let world_y = py + dy;
                 
-                let span = if dist > RENDER_RADIUS * RENDER_RADIUS {
-                    // Out of view range
-                    Span::styled("  ", Style::default().fg(Color::DarkGray))
-                } else if world_x < 0 || world_y < 0 || 
-                        world_x >= map.width as i32 || 
+                let span = if world_x < 0 || world_y < 0 ||
+                        world_x >= map.width as i32 ||
                         world_y >= map.height as i32 {
                     // Out of bounds - show empty space
                     Span::styled("  ", Style::default().fg(Color::DarkGray))
@@ -504,40 +1363,24 @@ impl TuiRenderer {
                         x: world_x as usize,
                         y: world_y as usize,
                     };
+                    let tile = &map.tiles[world_y as usize][world_x as usize];
+                    let visible = map.is_visible(&current_pos);
 
-                    if current_pos.x == player_pos.x && current_pos.y == player_pos.y {
+                    if !tile.seen {
+                        // Never seen - hidden
+                        Span::styled("  ", Style::default().bg(Color::Black))
+                    } else if current_pos.x == player_pos.x && current_pos.y == player_pos.y {
                         // Player position
                         Span::styled("@ ", Style::default().bold())
+                    } else if visible && map.npc_at(&current_pos).is_some() {
+                        Span::styled("& ", Style::default().fg(npc_color))
                     } else {
-                        let tile = &map.tiles[world_y as usize][world_x as usize];
-                        if let Some(feature) = &tile.feature {
-                            // Features
-                            let symbol = match feature.feature_type {
-                                FeatureType::Market => "M ",
-                                FeatureType::Temple => "T ",
-                                FeatureType::Tavern => "A ",
-                                FeatureType::Blacksmith => "B ",
-                                FeatureType::Garden => "* ",
-                                FeatureType::TrainingGround => "X ",
-                                FeatureType::Storage => "S ",
-                            };
-                            Span::styled(symbol, Style::default().fg(Color::Yellow))
-                        } else {
-                            // Regular tiles
-                            let (symbol, style) = match tile.tile_type {
-                                LocationTileType::Ground => (". ", Style::default().fg(Color::DarkGray)),
-                                LocationTileType::Wall => ("# ", Style::default().fg(Color::White)),
-                                LocationTileType::Water => ("~ ", Style::default().fg(Color::Blue)),
-                                LocationTileType::HumanRoad => ("= ", Style::default().fg(Color::Gray)),
-                                LocationTileType::ElfPath => ("- ", Style::default().fg(Color::Green)),
-                                LocationTileType::OrcTrail => (": ", Style::default().fg(Color::Red)),
-                                LocationTileType::HumanHouse => ("H ", Style::default().fg(Color::White)),
-                                LocationTileType::ElfTreehouse => ("T ", Style::default().fg(Color::Green)),
-                                LocationTileType::OrcHut => ("O ", Style::default().fg(Color::Red)),
-                                LocationTileType::Trading => ("$ ", Style::default().fg(Color::Yellow)),
-                                LocationTileType::Shrine => ("^ ", Style::default().fg(Color::Magenta)),
-                            };
+                        let (symbol, style) = self.get_location_tile_appearance(tile);
+                        if visible {
                             Span::styled(symbol, style)
+                        } else {
+                            // Remembered but out of sight - dimmed
+                            Span::styled(symbol, style.dim())
                         }
                     }
                 };
@@ -546,8 +1389,12 @@ impl TuiRenderer {
             lines.push(Line::from(row));
         }
 
-        let location_title = format!("{} ({}, {}) - Points of Interest:", 
-            title, player_pos.x, player_pos.y);
+        let floor = match map.level {
+            0 => String::new(),
+            level => format!(" [level {}]", level),
+        };
+        let location_title = format!("{}{} ({}, {}) - Points of Interest:", 
+            title, floor, player_pos.x, player_pos.y);
         let mut text = Text::from(lines);
         
         // Add nearby points of interest only
@@ -556,7 +1403,8 @@ impl TuiRenderer {
             // Update to use Position fields
             let dx = poi.position.x as i32 - px;
             let dy = poi.position.y as i32 - py;
-            if dx*dx + dy*dy <= RENDER_RADIUS*RENDER_RADIUS {
+            let seen = map.tiles[poi.position.y][poi.position.x].seen;
+            if seen && dx*dx + dy*dy <= RENDER_RADIUS*RENDER_RADIUS {
                 poi_lines.push(Line::from(format!(
                     "{} at ({}, {})",
                     poi.feature.name,
@@ -565,6 +1413,16 @@ impl TuiRenderer {
                 )));
             }
         }
+        for npc in &map.npc_spots {
+            if map.is_visible(&npc.position) {
+                poi_lines.push(Line::from(format!(
+                    "A {} at ({}, {})",
+                    npc.role.name(),
+                    npc.position.x,
+                    npc.position.y
+                )));
+            }
+        }
         text.extend(poi_lines);
 
         Paragraph::new(text)
@@ -575,6 +1433,63 @@ impl TuiRenderer {
 
 
 
+// Modifiers in the character sheet: "+2", "-1", or "." for none
+fn signed(value: i32) -> String {
+    if value == 0 { ".".to_string() } else { format!("{value:+}") }
+}
+
+// What the player can do where they stand, as shown on the actions bar
+fn interaction_labels(game: &Game) -> Vec<(Interaction, String)> {
+    game.available_interactions()
+        .into_iter()
+        .map(|interaction| {
+            let label = match (interaction, game.nearby_feature()) {
+                (Interaction::Visit, Some(feature)) => format!("Visit {}", feature.name),
+                _ => interaction.label().to_string(),
+            };
+            (interaction, label)
+        })
+        .collect()
+}
+
+// Colour of the people in a settlement, by how they treat the player
+fn disposition_color(disposition: Option<Attitude>) -> Color {
+    match disposition {
+        Some(Attitude::Friendly) => Color::Green,
+        Some(Attitude::Wary) => Color::Yellow,
+        Some(Attitude::Hostile) => Color::Red,
+        Some(Attitude::Neutral) | None => Color::Cyan,
+    }
+}
+
+// "cheap" or "dear" when local prices for the item's goods are well off
+// their usual value
+fn price_note(view: &TradeView, item: &Item, game: &Game) -> &'static str {
+    match item.goods().map(|goods| view.market.factor(goods, &game.clock)) {
+        Some(factor) if factor < CHEAP => "cheap",
+        Some(factor) if factor > DEAR => "dear",
+        _ => "",
+    }
+}
+
+// e.g. "Level: 2 (40/100 XP)"
+fn level_line(character: &Character) -> String {
+    let (progress, needed) = character.level_progress();
+    format!("Level: {} ({}/{} XP)", character.level, progress, needed)
+}
+
+// e.g. "Power Attack (4 Stamina, ready in 2)"
+fn ability_label(character: &Character, index: usize) -> String {
+    let ability = &character.abilities()[index];
+    let resource = character.class_def().map_or("", |class| class.resource.as_str());
+    let cooldown = character.cooldowns.get(index).copied().unwrap_or(0);
+    if cooldown > 0 {
+        format!("{} ({} {}, ready in {})", ability.name, ability.cost, resource, cooldown)
+    } else {
+        format!("{} ({} {}) - {}", ability.name, ability.cost, resource, ability.description)
+    }
+}
+
 pub fn init_terminal() -> Result<GameTerminal, Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
@@ -613,4 +1528,130 @@ impl Renderer for TuiRenderer {
     fn shutdown(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         TuiRenderer::shutdown(self)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::harness::render_to_text;
+    use crate::systems::character::{Attributes, Character};
+    use crate::systems::status::BLESSED;
+    use crate::generators::location_generator::Feature;
+    use crate::systems::reputation::Faction;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // Fixed world and player so the output only changes when the rendering
+    // does. Run with UPDATE_SNAPSHOTS=1 to accept new output.
+    const WORLD_SEED: u32 = 42;
+    const WORLD_WIDTH: usize = 96;
+    const WORLD_HEIGHT: usize = 64;
+
+    // A game with the player standing on the first settlement of a small world
+    fn test_game() -> Game {
+        let world = World::new(WORLD_SEED, WORLD_WIDTH, WORLD_HEIGHT);
+        let character = Character::new("Tester".to_string(), "Warrior".to_string(), Species::Human, Attributes::point_buy());
+        let player = Player::new(character, 0, 0);
+        let mut game = Game::new(player, world);
+        game.player.world_pos = game.world
+            .find_nearest_species(&Position::new(0, 0), Species::Human)
+            .expect("the test world has a human settlement");
+        game.update_visibility();
+        game
+    }
+
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/render/snapshots")
+            .join(format!("{name}.txt"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!("missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it", path.display())
+        });
+        assert!(
+            expected == actual,
+            "snapshot {name} changed; run with UPDATE_SNAPSHOTS=1 to accept\n--- expected\n{expected}\n--- actual\n{actual}"
+        );
+    }
+
+    #[test]
+    fn world_view() {
+        let game = test_game();
+        assert_snapshot("world_view", &render_to_text(&game));
+    }
+
+    #[test]
+    fn location_view() {
+        let mut game = test_game();
+        game.enter_location();
+        game.update_visibility();
+        assert!(matches!(game.phase, GamePhase::PlayingLocation(_)));
+        assert_snapshot("location_view", &render_to_text(&game));
+    }
+
+    #[test]
+    fn full_map() {
+        let mut game = test_game();
+        game.phase = GamePhase::Map(MapView::new(game.player.world_pos));
+        assert_snapshot("full_map", &render_to_text(&game));
+    }
+
+    #[test]
+    fn title_menu() {
+        let game = Game::title_screen();
+        assert_snapshot("title_menu", &render_to_text(&game));
+    }
+
+    #[test]
+    fn game_over() {
+        let mut game = test_game();
+        game.clock.advance_hours(50);
+        game.player.character.gain_xp(120);
+        game.end_run("Eaten by a grue");
+        assert_snapshot("game_over", &render_to_text(&game));
+    }
+
+    #[test]
+    fn inventory() {
+        let mut game = test_game();
+        game.player.character.inventory.push(Item::new("Iron helm"));
+        game.open_inventory();
+        assert_snapshot("inventory", &render_to_text(&game));
+    }
+
+    #[test]
+    fn character_sheet() {
+        let mut game = test_game();
+        game.player.character.gain_xp(60);
+        game.player.character.apply_effect(BLESSED);
+        game.open_character_sheet();
+        assert_snapshot("character_sheet", &render_to_text(&game));
+    }
+
+    #[test]
+    fn feature() {
+        let mut game = test_game();
+        game.player.character.health = 60;
+        game.open_feature(Feature { name: "Sacred Temple".to_string(), feature_type: FeatureType::Temple });
+        assert_snapshot("feature", &render_to_text(&game));
+    }
+
+    #[test]
+    fn trade() {
+        let mut game = test_game();
+        game.open_trade(vec!["Rations".to_string(), "Silver ring".to_string()]);
+        assert_snapshot("trade", &render_to_text(&game));
+    }
+
+    #[test]
+    fn reputation() {
+        let mut game = test_game();
+        let home = game.world.get_tile(&game.player.world_pos).location.clone().unwrap();
+        game.player.reputation.change(Faction::of(&home), 30);
+        game.open_reputation();
+        assert_snapshot("reputation", &render_to_text(&game));
+    }
+}
@@ -1,100 +1,665 @@
 use rand::Rng;
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use serde::{Deserialize, Serialize};
 use crate::systems::location::Species;
+use crate::systems::class::{Ability, ClassDef, class_def, classes};
+use crate::systems::item::{Item, MAX_CONDITION};
+use crate::systems::equipment::{EquipSlot, Equipment, Slot};
+use crate::systems::status::{Stacking, StatusEffect, effect_def};
 
+// Points to spend on attributes when building a character by point-buy
+pub const POINT_BUY_POINTS: i32 = 12;
+// How many points a single attribute can take
+const POINT_BUY_MAX_PER_ATTRIBUTE: i32 = 6;
+// What point-buy starts from: the lowest possible roll
+const POINT_BUY_BASE: Attributes = Attributes {
+    strength: 8,
+    dexterity: 8,
+    intelligence: 8,
+    constitution: 8,
+    luck: 1,
+};
+// Max health for each point of constitution
+const HEALTH_PER_CONSTITUTION: i32 = 10;
+
+// Each level costs this much more XP than the one before: 50 to reach
+// level 2, another 100 for level 3, and so on
+const XP_PER_LEVEL: i32 = 50;
+// Attribute points to spend on every level gained
+pub const POINTS_PER_LEVEL: i32 = 2;
+// Max health gained per level by characters without a class
+const BASE_HEALTH_GROWTH: i32 = 10;
+
+// Total XP needed to reach `level`
+pub fn xp_for_level(level: u32) -> i32 {
+    let level = level.max(1) as i32;
+    XP_PER_LEVEL * level * (level - 1) / 2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Intelligence,
+    Constitution,
+    Luck,
+}
+
+pub const ATTRIBUTES: [Attribute; 5] = [
+    Attribute::Strength,
+    Attribute::Dexterity,
+    Attribute::Intelligence,
+    Attribute::Constitution,
+    Attribute::Luck,
+];
+
+impl Attribute {
+    pub fn label(&self) -> &str {
+        match self {
+            Attribute::Strength => "Strength",
+            Attribute::Dexterity => "Dexterity",
+            Attribute::Intelligence => "Intelligence",
+            Attribute::Constitution => "Constitution",
+            Attribute::Luck => "Luck",
+        }
+    }
+
+    // For the stats panel, where space is short
+    pub fn short_label(&self) -> &str {
+        match self {
+            Attribute::Strength => "Str",
+            Attribute::Dexterity => "Dex",
+            Attribute::Intelligence => "Int",
+            Attribute::Constitution => "Con",
+            Attribute::Luck => "Luck",
+        }
+    }
+}
+
+// A character's own attribute values, before species, class and equipment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attributes {
+    pub strength: i32,
+    pub dexterity: i32,
+    pub intelligence: i32,
+    pub constitution: i32,
+    pub luck: i32,
+}
+
+impl Attributes {
+    pub fn point_buy() -> Self {
+        POINT_BUY_BASE
+    }
+
+    pub fn roll(rng: &mut impl Rng) -> Self {
+        Self {
+            strength: rng.gen_range(8..13),
+            dexterity: rng.gen_range(8..13),
+            intelligence: rng.gen_range(8..13),
+            constitution: rng.gen_range(8..13),
+            luck: rng.gen_range(1..10),
+        }
+    }
+
+    pub fn get(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Constitution => self.constitution,
+            Attribute::Luck => self.luck,
+        }
+    }
+
+    fn get_mut(&mut self, attribute: Attribute) -> &mut i32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Dexterity => &mut self.dexterity,
+            Attribute::Intelligence => &mut self.intelligence,
+            Attribute::Constitution => &mut self.constitution,
+            Attribute::Luck => &mut self.luck,
+        }
+    }
+
+    fn points_in(&self, attribute: Attribute) -> i32 {
+        self.get(attribute) - POINT_BUY_BASE.get(attribute)
+    }
+
+    pub fn points_left(&self) -> i32 {
+        POINT_BUY_POINTS - ATTRIBUTES.iter().map(|&a| self.points_in(a)).sum::<i32>()
+    }
+
+    // Spend a point on `attribute`. Returns false when there is none left
+    // or the attribute is maxed out.
+    pub fn raise(&mut self, attribute: Attribute) -> bool {
+        if self.points_left() == 0 || self.points_in(attribute) >= POINT_BUY_MAX_PER_ATTRIBUTE {
+            return false;
+        }
+        *self.get_mut(attribute) += 1;
+        true
+    }
+
+    // Take back a point spent on `attribute`
+    pub fn lower(&mut self, attribute: Attribute) -> bool {
+        if self.points_in(attribute) == 0 {
+            return false;
+        }
+        *self.get_mut(attribute) -= 1;
+        true
+    }
+}
+
+// Added to a character's attributes, e.g. by their species or class.
+// `health` is added to max health directly.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatModifiers {
+    pub health: i32,
+    pub strength: i32,
+    pub dexterity: i32,
+    pub intelligence: i32,
+    pub constitution: i32,
+    pub luck: i32,
+}
+
+impl StatModifiers {
+    pub fn get(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Constitution => self.constitution,
+            Attribute::Luck => self.luck,
+        }
+    }
+
+    // e.g. "Health -10, Dex +2"
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.health != 0 {
+            parts.push(format!("Health {:+}", self.health));
+        }
+        for attribute in ATTRIBUTES {
+            let value = self.get(attribute);
+            if value != 0 {
+                parts.push(format!("{} {:+}", attribute.short_label(), value));
+            }
+        }
+        if parts.is_empty() {
+            "no stat changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+impl Add for StatModifiers {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            health: self.health + other.health,
+            strength: self.strength + other.strength,
+            dexterity: self.dexterity + other.dexterity,
+            intelligence: self.intelligence + other.intelligence,
+            constitution: self.constitution + other.constitution,
+            luck: self.luck + other.luck,
+        }
+    }
+}
+
+impl Mul<i32> for StatModifiers {
+    type Output = Self;
+
+    fn mul(self, times: i32) -> Self {
+        Self {
+            health: self.health * times,
+            strength: self.strength * times,
+            dexterity: self.dexterity * times,
+            intelligence: self.intelligence * times,
+            constitution: self.constitution * times,
+            luck: self.luck * times,
+        }
+    }
+}
+
+impl Sum for StatModifiers {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Character {
     pub name: String,
     pub species: Species,
     pub class: String,
+    // Before species, class and equipment; see attribute() for the totals
+    pub attributes: Attributes,
     pub health: i32,
-    pub max_health: i32,
-    pub attack: i32,
-    pub dodge: i32,
-    pub luck: i32,
     pub xp: i32,
+    pub level: u32,
+    // Attribute points earned by levelling up but not yet spent
+    pub unspent_points: i32,
+    // What class abilities spend, e.g. stamina or mana
+    pub resource: i32,
+    pub max_resource: i32,
+    // Turns until each class ability can be used again
+    pub cooldowns: Vec<u32>,
+    pub inventory: Vec<Item>,
+    pub equipment: Equipment,
+    pub effects: Vec<StatusEffect>,
 }
 
 impl Character {
-    pub fn new(
-        name: String,
-        class: String,
-        species: Species,
-        health: i32,
-        max_health: i32,
-        attack: i32,
-        dodge: i32,
-        luck: i32,
-    ) -> Self {
-        Character {
+    pub fn new(name: String, class: String, species: Species, attributes: Attributes) -> Self {
+        let class_def = class_def(&class);
+        let max_resource = class_def.map_or(0, |def| def.max_resource);
+        let mut character = Character {
             name: name.to_string(),
             species,
             class: class.to_string(),
-            health,
-            max_health,
-            attack,
-            dodge,
-            luck,
+            attributes,
+            health: 0,
             xp: 0,
+            level: 1,
+            unspent_points: 0,
+            resource: max_resource,
+            max_resource,
+            cooldowns: vec![0; class_def.map_or(0, |def| def.abilities.len())],
+            inventory: Vec::new(),
+            equipment: Equipment::default(),
+            effects: Vec::new(),
+        };
+        // Starting gear is worn where it fits and carried otherwise
+        for name in class_def.map_or(&[][..], |def| &def.equipment[..]) {
+            let item = Item::new(name);
+            match character.slot_for(&item).map(|slot| character.equipment.place_for(slot)) {
+                Ok(place) if character.equipment.get(place).is_none() => {
+                    character.equipment.put_on(place, item);
+                }
+                _ => character.inventory.push(item),
+            }
         }
-    }
-
-    pub fn create_human(name: String) -> Self {
-        Self::new(
-            name,
-            "Adventurer".to_string(),
-            Species::Human,
-            100,
-            100,
-            10,
-            10,
-            5,
-        )
+        character.health = character.max_health();
+        character
     }
 
     pub fn create_random() -> Self {
         let mut rng = rand::thread_rng();
-        
-        let classes = vec!["Warrior", "Mage", "Rogue", "Cleric"];
-        let class = classes[rng.gen_range(0..classes.len())].to_string();
-        
-        let health = rng.gen_range(80..120);
-        let attack = rng.gen_range(8..12);
-        let dodge = rng.gen_range(8..12);
-        let luck = rng.gen_range(1..10);
+        let class = classes()[rng.gen_range(0..classes().len())].name.clone();
+        Self::new("Player".to_string(), class, Species::Human, Attributes::roll(&mut rng))
+    }
 
-        Self::new(
-            "Player".to_string(),
-            class,
-            Species::Human,
-            health,
-            health,
-            attack,
-            dodge,
-            luck,
-        )
+    pub fn class_def(&self) -> Option<&'static ClassDef> {
+        class_def(&self.class)
     }
 
-    pub fn stats(&self) -> String {
-        format!(
-            "{} hp: {} attack: {} dodge: {} luck: {} xp: {}",
-            self.class, self.health, self.attack, self.dodge, self.luck, self.xp
-        )
+    // What each source adds to the character's own attributes
+    pub fn modifiers(&self) -> [(&'static str, StatModifiers); 4] {
+        let levels = self.level.max(1) as i32 - 1;
+        let class = match self.class_def() {
+            Some(def) => def.stats + def.growth * levels,
+            None => StatModifiers { health: BASE_HEALTH_GROWTH * levels, ..StatModifiers::default() },
+        };
+        let equipment = self.equipment.items().map(Item::worn_stats).sum();
+        let effects = self.effects.iter().map(StatusEffect::stats).sum();
+        [
+            ("Species", self.species.traits().stats),
+            ("Class", class),
+            ("Equipment", equipment),
+            ("Effects", effects),
+        ]
     }
 
-    pub fn damage(&mut self, damage_amount: i32) {
-        self.health -= damage_amount;
-        self.xp += 2;
+    fn total_modifiers(&self) -> StatModifiers {
+        self.modifiers().iter().map(|(_, modifiers)| *modifiers).sum()
     }
 
-    pub fn heal(&mut self, heal_amount: i32) {
-        self.health += heal_amount;
-        self.xp += 1;
+    // An attribute with all modifiers applied
+    pub fn attribute(&self, attribute: Attribute) -> i32 {
+        self.attributes.get(attribute) + self.total_modifiers().get(attribute)
+    }
+
+    pub fn max_health(&self) -> i32 {
+        let health = self.attribute(Attribute::Constitution) * HEALTH_PER_CONSTITUTION + self.total_modifiers().health;
+        health.max(1)
     }
 
     pub fn attack(&self) -> i32 {
-        self.attack + self.luck / 2 + self.xp / 10
+        self.attribute(Attribute::Strength) + self.attribute(Attribute::Luck) / 2
     }
 
     pub fn dodge(&self) -> i32 {
-        self.dodge + self.luck / 2 + self.xp / 10
+        self.attribute(Attribute::Dexterity) + self.attribute(Attribute::Luck) / 2
+    }
+
+    // Added to the damage of bolts and the health restored by heals
+    pub fn spell_power(&self) -> i32 {
+        (self.attribute(Attribute::Intelligence) / 2).max(0)
+    }
+
+    // Where `item` would be worn, or why it can't be
+    pub fn slot_for(&self, item: &Item) -> Result<Slot, String> {
+        match item.slot {
+            None => Err(format!("The {} can't be worn.", item.name)),
+            Some(slot) if self.species.traits().cannot_wear.contains(&slot) => {
+                Err(format!("{}s can't wear {}.", self.species, slot.plural()))
+            }
+            Some(slot) => Ok(slot),
+        }
+    }
+
+    // Wear the pack item at `index`, putting whatever was worn in its place
+    // back in the pack. Returns what happened, for the message box.
+    pub fn equip(&mut self, index: usize) -> Result<String, String> {
+        let item = self.inventory.get(index).ok_or("There is nothing there to wear.")?;
+        let slot = self.slot_for(item)?;
+        let item = self.inventory.remove(index);
+        let name = item.name.clone();
+        let place = self.equipment.place_for(slot);
+        let message = match self.equipment.put_on(place, item) {
+            Some(old) => {
+                let message = format!("You put on the {} and stow the {}.", name, old.name);
+                self.inventory.push(old);
+                message
+            }
+            None => format!("You put on the {}.", name),
+        };
+        self.health = self.health.min(self.max_health());
+        Ok(message)
+    }
+
+    // Take off whatever is worn at `place` and put it in the pack
+    pub fn unequip(&mut self, place: EquipSlot) -> Result<String, String> {
+        let item = self.equipment.take_off(place).ok_or("You aren't wearing anything there.")?;
+        let message = format!("You take off the {}.", item.name);
+        self.inventory.push(item);
+        self.health = self.health.min(self.max_health());
+        Ok(message)
+    }
+
+    // A fight wears down the weapon and armour being worn; jewellery keeps.
+    // Returns the names of the items that broke.
+    pub fn wear_gear(&mut self, amount: u32) -> Vec<String> {
+        let mut broken = Vec::new();
+        for item in self.equipment.items_mut() {
+            if matches!(item.slot, Some(Slot::Ring | Slot::Amulet)) || item.is_broken() {
+                continue;
+            }
+            item.condition = item.condition.saturating_sub(amount);
+            if item.is_broken() {
+                broken.push(item.name.clone());
+            }
+        }
+        self.health = self.health.min(self.max_health());
+        broken
+    }
+
+    // Points of condition worn gear has lost
+    pub fn gear_damage(&self) -> u32 {
+        self.equipment.items().map(|item| MAX_CONDITION - item.condition).sum()
+    }
+
+    pub fn repair_gear(&mut self) {
+        for item in self.equipment.items_mut() {
+            item.condition = MAX_CONDITION;
+        }
+    }
+
+    pub fn abilities(&self) -> &'static [Ability] {
+        self.class_def().map_or(&[], |def| &def.abilities)
+    }
+
+    // Why ability `index` can't be used right now, if it can't
+    pub fn ability_unavailable(&self, index: usize) -> Option<String> {
+        let ability = self.abilities().get(index)?;
+        let cooldown = self.cooldowns.get(index).copied().unwrap_or(0);
+        if cooldown > 0 {
+            Some(format!("{} will be ready in {} turns.", ability.name, cooldown))
+        } else if self.resource < ability.cost {
+            let resource = self.class_def().map_or("", |def| def.resource.as_str());
+            Some(format!("Not enough {} for {}.", resource.to_lowercase(), ability.name))
+        } else {
+            None
+        }
+    }
+
+    // Pay for ability `index` and start its cooldown
+    pub fn spend_ability(&mut self, index: usize) {
+        let Some(ability) = self.abilities().get(index) else {
+            return;
+        };
+        self.resource -= ability.cost;
+        if let Some(cooldown) = self.cooldowns.get_mut(index) {
+            *cooldown = ability.cooldown;
+        }
+    }
+
+    pub fn tick_cooldowns(&mut self) {
+        for cooldown in &mut self.cooldowns {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+    }
+
+    pub fn rest(&mut self) {
+        self.effects.retain(|effect| !effect.def().is_some_and(|def| def.cured_by_rest));
+        self.health = self.max_health();
+        self.resource = self.max_resource;
+        self.cooldowns.fill(0);
+    }
+
+    pub fn has_effect(&self, name: &str) -> bool {
+        self.effects.iter().any(|effect| effect.name == name)
+    }
+
+    // Put the effect called `name` on the character, following its stacking
+    // rule if it is already there. Returns false when nothing changed.
+    pub fn apply_effect(&mut self, name: &str) -> bool {
+        let Some(def) = effect_def(name) else {
+            return false;
+        };
+        let Some(effect) = self.effects.iter_mut().find(|effect| effect.name == name) else {
+            self.effects.push(StatusEffect { name: def.name.clone(), turns_left: def.duration, stacks: 1 });
+            return true;
+        };
+        match def.stacking {
+            Stacking::Refresh => effect.turns_left = def.duration,
+            Stacking::Extend => effect.turns_left += def.duration,
+            Stacking::Stack => {
+                effect.stacks = (effect.stacks + 1).min(def.max_stacks.max(1));
+                effect.turns_left = def.duration;
+            }
+            Stacking::Ignore => return false,
+        }
+        true
+    }
+
+    pub fn remove_effect(&mut self, name: &str) -> bool {
+        let before = self.effects.len();
+        self.effects.retain(|effect| effect.name != name);
+        self.effects.len() < before
+    }
+
+    // A turn passes for every effect: health is gained or lost and the
+    // durations run down. Returns the names of the effects that wore off.
+    pub fn tick_effects(&mut self) -> Vec<String> {
+        let change: i32 = self
+            .effects
+            .iter()
+            .map(|effect| effect.def().map_or(0, |def| def.health) * effect.stacks as i32)
+            .sum();
+        self.health = (self.health + change).min(self.max_health());
+        let mut expired = Vec::new();
+        for effect in &mut self.effects {
+            effect.turns_left = effect.turns_left.saturating_sub(1);
+            if effect.turns_left == 0 {
+                expired.push(effect.name.clone());
+            }
+        }
+        self.effects.retain(|effect| effect.turns_left > 0);
+        // Losing a boost to constitution can leave too much health
+        self.health = self.health.min(self.max_health());
+        expired
     }
-}
\ No newline at end of file
+
+    // Heal up to full health. Returns how much was restored.
+    pub fn restore_health(&mut self, amount: i32) -> i32 {
+        let restored = amount.min(self.max_health() - self.health).max(0);
+        self.health += restored;
+        restored
+    }
+
+    // Add experience, levelling up as often as it allows. Returns how many
+    // levels were gained.
+    pub fn gain_xp(&mut self, amount: i32) -> u32 {
+        self.xp += amount;
+        let mut gained = 0;
+        while self.xp >= xp_for_level(self.level + 1) {
+            // Class growth comes with the level; the new health is
+            // there to use straight away
+            let max_health = self.max_health();
+            self.level += 1;
+            self.health += self.max_health() - max_health;
+            self.unspent_points += POINTS_PER_LEVEL;
+            gained += 1;
+        }
+        gained
+    }
+
+    // XP earned since the current level, and how much the next one takes
+    pub fn level_progress(&self) -> (i32, i32) {
+        let floor = xp_for_level(self.level);
+        (self.xp - floor, xp_for_level(self.level + 1) - floor)
+    }
+
+    // Spend a point earned by levelling up. Returns false when there is none.
+    pub fn spend_point(&mut self, attribute: Attribute) -> bool {
+        if self.unspent_points <= 0 {
+            return false;
+        }
+        self.unspent_points -= 1;
+        self.raise_attribute(attribute);
+        true
+    }
+
+    // Add one to a base attribute; new health is there to use straight away
+    pub fn raise_attribute(&mut self, attribute: Attribute) {
+        let max_health = self.max_health();
+        *self.attributes.get_mut(attribute) += 1;
+        self.health += self.max_health() - max_health;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::status::{CURSED, EXHAUSTED};
+
+    fn warrior() -> Character {
+        Character::new("Brom".to_string(), "Warrior".to_string(), Species::Orc, Attributes::point_buy())
+    }
+
+    #[test]
+    fn derived_values_include_every_modifier() {
+        let brom = warrior();
+        // Point-buy 8, orc +2, warrior +2, longsword +3
+        assert_eq!(brom.attribute(Attribute::Strength), 15);
+        assert_eq!(brom.attack(), 15 + brom.attribute(Attribute::Luck) / 2);
+        // Constitution 8 + 1 + 2, and chainmail +2
+        assert_eq!(brom.max_health(), 13 * HEALTH_PER_CONSTITUTION);
+        assert_eq!(brom.health, 13 * HEALTH_PER_CONSTITUTION);
+    }
+
+    #[test]
+    fn levels_follow_the_xp_curve() {
+        let mut brom = warrior();
+        let max_health = brom.max_health();
+        let growth = brom.class_def().expect("warriors are a class").growth;
+
+        assert_eq!(brom.gain_xp(xp_for_level(2) - 1), 0);
+        assert_eq!(brom.level, 1);
+        // Enough for two levels at once
+        assert_eq!(brom.gain_xp(xp_for_level(3) - brom.xp), 2);
+        assert_eq!(brom.level, 3);
+        assert_eq!(brom.max_health(), max_health + 2 * growth.health);
+        assert_eq!(brom.health, brom.max_health());
+        assert_eq!(brom.unspent_points, 2 * POINTS_PER_LEVEL);
+        assert_eq!(brom.level_progress(), (0, xp_for_level(4) - xp_for_level(3)));
+
+        let max_health = brom.max_health();
+        assert!(brom.spend_point(Attribute::Constitution));
+        assert_eq!(brom.max_health(), max_health + HEALTH_PER_CONSTITUTION);
+        brom.unspent_points = 0;
+        assert!(!brom.spend_point(Attribute::Strength));
+    }
+
+    #[test]
+    fn equipping_gear() {
+        let mut brom = warrior();
+        let strength = brom.attribute(Attribute::Strength);
+        assert_eq!(brom.equipment.get(EquipSlot::Weapon).map(|item| item.name.as_str()), Some("Longsword"));
+
+        brom.inventory.push(Item::new("Mace"));
+        let mace = brom.inventory.len() - 1;
+        assert_eq!(brom.equip(mace), Ok("You put on the Mace and stow the Longsword.".to_string()));
+        assert_eq!(brom.attribute(Attribute::Strength), strength - 1);
+
+        assert_eq!(brom.unequip(EquipSlot::Body), Ok("You take off the Chainmail.".to_string()));
+        assert_eq!(brom.health, brom.max_health());
+
+        // Rings fill both hands before replacing one
+        brom.inventory.push(Item::new("Silver ring"));
+        brom.inventory.push(Item::new("Ring of vigour"));
+        let ring = brom.inventory.len() - 2;
+        brom.equip(ring).unwrap();
+        brom.equip(ring).unwrap();
+        assert!(brom.equipment.get(EquipSlot::LeftRing).is_some());
+        assert!(brom.equipment.get(EquipSlot::RightRing).is_some());
+    }
+
+    #[test]
+    fn bears_cant_wear_boots() {
+        let mut bear = Character::new("Ursa".to_string(), "Warrior".to_string(), Species::Bear, Attributes::point_buy());
+        bear.inventory.push(Item::new("Leather boots"));
+        let boots = bear.inventory.len() - 1;
+        assert_eq!(bear.equip(boots), Err("Bears can't wear footwear.".to_string()));
+        assert!(bear.equipment.get(EquipSlot::Feet).is_none());
+    }
+
+    #[test]
+    fn status_effects_stack_and_wear_off() {
+        let mut brom = warrior();
+        let luck = brom.attribute(Attribute::Luck);
+        let health = brom.health;
+
+        assert!(brom.apply_effect("Poisoned"));
+        assert!(brom.apply_effect("Poisoned"));
+        assert_eq!(brom.effects[0].stacks, 2);
+        brom.tick_effects();
+        assert_eq!(brom.health, health - 4);
+
+        // Curses pile up their duration instead
+        brom.apply_effect(CURSED);
+        brom.apply_effect(CURSED);
+        let cursed = brom.effects.iter().find(|effect| effect.name == CURSED).unwrap();
+        assert_eq!(cursed.turns_left, 2 * effect_def(CURSED).unwrap().duration);
+        assert_eq!(brom.attribute(Attribute::Luck), luck - 3);
+
+        let duration = effect_def("Poisoned").unwrap().duration;
+        let mut expired = Vec::new();
+        for _ in 1..duration {
+            expired.extend(brom.tick_effects());
+        }
+        assert_eq!(expired, vec!["Poisoned".to_string()]);
+        assert!(!brom.has_effect("Poisoned"));
+
+        // Exhaustion goes with a night's rest, the curse doesn't
+        brom.apply_effect(EXHAUSTED);
+        assert!(!brom.apply_effect(EXHAUSTED));
+        brom.rest();
+        assert!(!brom.has_effect(EXHAUSTED));
+        assert!(brom.has_effect(CURSED));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::systems::character::StatModifiers;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const CLASS_DATA: &str = include_str!("../../data/classes.toml");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClassFile {
+    class: Vec<ClassDef>,
+}
+
+static CLASSES: LazyLock<Vec<ClassDef>> = LazyLock::new(|| {
+    let file: ClassFile =
+        toml::from_str(CLASS_DATA).unwrap_or_else(|e| panic!("data/classes.toml is invalid: {e}"));
+    file.class
+});
+
+// A character class, as described in data/classes.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClassDef {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub stats: StatModifiers,
+    // Added on every level gained
+    #[serde(default)]
+    pub growth: StatModifiers,
+    // What abilities spend, e.g. "Mana"
+    pub resource: String,
+    pub max_resource: i32,
+    #[serde(default)]
+    pub equipment: Vec<String>,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ability {
+    pub name: String,
+    pub description: String,
+    pub cost: i32,
+    // Turns before it can be used again
+    pub cooldown: u32,
+    pub effect: AbilityEffect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum AbilityEffect {
+    // An attack dealing `multiplier` times the usual damage
+    Strike { multiplier: i32 },
+    // Damage that always hits
+    Bolt { damage: i32 },
+    // Escape a fight, or avoid encounters for `steps` steps on the world map
+    Stealth { steps: u32 },
+    Heal { amount: i32 },
+}
+
+impl AbilityEffect {
+    // Whether the ability only makes sense against an enemy
+    pub fn combat_only(&self) -> bool {
+        matches!(self, AbilityEffect::Strike { .. } | AbilityEffect::Bolt { .. })
+    }
+}
+
+// All classes in the order they are offered at character creation
+pub fn classes() -> &'static [ClassDef] {
+    &CLASSES
+}
+
+pub fn class_def(name: &str) -> Option<&'static ClassDef> {
+    CLASSES.iter().find(|class| class.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_class_has_an_ability_it_can_afford() {
+        assert!(!classes().is_empty());
+        for class in classes() {
+            assert!(!class.abilities.is_empty(), "{} has no abilities", class.name);
+            for ability in &class.abilities {
+                assert!(ability.cost <= class.max_resource, "{} can never afford {}", class.name, ability.name);
+            }
+        }
+    }
+}
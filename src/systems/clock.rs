@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+// A new game starts on the morning of day 1
+const START_TIME: u64 = 8 * SECONDS_PER_HOUR;
+
+// Walking one world tile takes this long per point of terrain move cost
+const WORLD_SECONDS_PER_MOVE_COST: u64 = 30 * SECONDS_PER_MINUTE;
+// One step inside a location
+const LOCAL_STEP_SECONDS: u64 = 6;
+// Dusk and dawn
+const NIGHT_START_HOUR: u64 = 20;
+const NIGHT_END_HOUR: u64 = 6;
+// A diagonal step covers about √2 times the distance of a straight one
+const DIAGONAL_PERCENT: u64 = 141;
+
+// In-game time, counted in seconds since the start of day 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GameClock {
+    pub seconds: u64,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self { seconds: START_TIME }
+    }
+}
+
+impl GameClock {
+    pub fn advance(&mut self, seconds: u64) {
+        self.seconds += seconds;
+    }
+
+    pub fn advance_hours(&mut self, hours: u64) {
+        self.advance(hours * SECONDS_PER_HOUR);
+    }
+
+    // Time since the start of day 1, in days
+    pub fn days(&self) -> f64 {
+        self.seconds as f64 / SECONDS_PER_DAY as f64
+    }
+
+    // Days are counted from 1
+    pub fn day(&self) -> u64 {
+        self.seconds / SECONDS_PER_DAY + 1
+    }
+
+    pub fn hour(&self) -> u64 {
+        self.seconds % SECONDS_PER_DAY / SECONDS_PER_HOUR
+    }
+
+    pub fn minute(&self) -> u64 {
+        self.seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE
+    }
+
+    // Whole hours passed since `seconds`
+    pub fn hours_since(&self, seconds: u64) -> u64 {
+        self.seconds.saturating_sub(seconds) / SECONDS_PER_HOUR
+    }
+
+    pub fn is_night(&self) -> bool {
+        let hour = self.hour();
+        !(NIGHT_END_HOUR..NIGHT_START_HOUR).contains(&hour)
+    }
+
+    pub fn describe(&self) -> String {
+        format!("Day {}, {:02}:{:02}", self.day(), self.hour(), self.minute())
+    }
+}
+
+// How long it takes to cross one world tile
+pub fn world_step_time(move_cost: u32, diagonal: bool) -> u64 {
+    step_time(move_cost as u64 * WORLD_SECONDS_PER_MOVE_COST, diagonal)
+}
+
+// How long one step inside a location takes
+pub fn local_step_time(diagonal: bool) -> u64 {
+    step_time(LOCAL_STEP_SECONDS, diagonal)
+}
+
+fn step_time(straight: u64, diagonal: bool) -> u64 {
+    if diagonal {
+        straight * DIAGONAL_PERCENT / 100
+    } else {
+        straight
+    }
+}
@@ -0,0 +1,141 @@
+use crate::systems::character::Character;
+use crate::systems::world::TerrainType;
+use rand::Rng;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const MONSTER_DATA: &str = include_str!("../../data/monsters.toml");
+
+// Lines of the fight kept for the combat log
+const LOG_LENGTH: usize = 12;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonsterFile {
+    monster: Vec<MonsterDef>,
+}
+
+static MONSTERS: LazyLock<Vec<MonsterDef>> = LazyLock::new(|| {
+    let file: MonsterFile =
+        toml::from_str(MONSTER_DATA).unwrap_or_else(|e| panic!("data/monsters.toml is invalid: {e}"));
+    file.monster
+});
+
+// A kind of creature, as described in data/monsters.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonsterDef {
+    pub name: String,
+    pub health: i32,
+    pub attack: i32,
+    pub dodge: i32,
+    pub xp: i32,
+    // Most coin it may carry
+    #[serde(default)]
+    pub gold: i32,
+    // Where it roams
+    pub terrain: Vec<TerrainType>,
+    #[serde(default)]
+    pub inflicts: Option<Affliction>,
+}
+
+// A status effect a monster's hits may cause
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Affliction {
+    pub effect: String,
+    // Percent chance per hit
+    pub chance: u32,
+}
+
+// Monsters that roam `terrain`
+pub fn monsters_in(terrain: TerrainType) -> Vec<&'static MonsterDef> {
+    MONSTERS.iter().filter(|monster| monster.terrain.contains(&terrain)).collect()
+}
+
+// The creature the player is fighting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enemy {
+    pub name: String,
+    pub health: i32,
+    pub max_health: i32,
+    pub attack: i32,
+    pub dodge: i32,
+    pub xp: i32,
+    pub gold: i32,
+    pub inflicts: Option<Affliction>,
+}
+
+impl From<&MonsterDef> for Enemy {
+    fn from(def: &MonsterDef) -> Self {
+        Self {
+            name: def.name.clone(),
+            health: def.health,
+            max_health: def.health,
+            attack: def.attack,
+            dodge: def.dodge,
+            xp: def.xp,
+            gold: def.gold,
+            inflicts: def.inflicts.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombatAction {
+    Attack,
+    // One of the player's class abilities
+    Ability(usize),
+    Flee,
+}
+
+// A fight in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct Combat {
+    pub enemy: Enemy,
+    pub log: Vec<String>,
+    // Highlighted entry of `actions`
+    pub selected: usize,
+}
+
+impl Combat {
+    pub fn new(enemy: Enemy) -> Self {
+        let log = vec![format!("A {} attacks!", enemy.name)];
+        Self { enemy, log, selected: 0 }
+    }
+
+    // What the player can do on their turn, in menu order
+    pub fn actions(character: &Character) -> Vec<CombatAction> {
+        let mut actions = vec![CombatAction::Attack];
+        actions.extend((0..character.abilities().len()).map(CombatAction::Ability));
+        actions.push(CombatAction::Flee);
+        actions
+    }
+
+    pub fn move_selection(&mut self, delta: i32, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected as i32 + delta).rem_euclid(len as i32) as usize;
+        }
+    }
+
+    pub fn log(&mut self, line: String) {
+        self.log.push(line);
+        if self.log.len() > LOG_LENGTH {
+            self.log.remove(0);
+        }
+    }
+}
+
+// Percent chance that an attack lands
+pub fn hit_chance(attack: i32, dodge: i32) -> i32 {
+    (50 + (attack - dodge) * 5).clamp(10, 95)
+}
+
+// Damage dealt by an attack, or None when it misses
+pub fn roll_attack(rng: &mut impl Rng, attack: i32, dodge: i32) -> Option<i32> {
+    if rng.gen_range(0..100) >= hit_chance(attack, dodge) {
+        return None;
+    }
+    Some(rng.gen_range(1..=6) + attack / 3)
+}
@@ -0,0 +1,267 @@
+use crate::generators::location_generator::location_seed;
+use crate::systems::clock::GameClock;
+use crate::systems::item::{Item, MAX_CONDITION, item_def, items};
+use crate::systems::location::{Industry, Location, LocationState};
+use crate::systems::position::Position;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::Deserialize;
+
+// Price multipliers for goods a settlement makes and goods it lacks
+const SUPPLY_FACTOR: f32 = 0.6;
+const DEMAND_FACTOR: f32 = 1.5;
+// Hard times push every price up
+const STRUGGLING_FACTOR: f32 = 1.25;
+// How far prices wander from day to day, either way
+const DRIFT: f32 = 0.15;
+// Share of the asking price a market pays for what the player sells
+const SELL_SHARE: f32 = 0.75;
+// Change in prices per point of the player's standing with the settlement:
+// up to a fifth off for the honoured, a fifth on for the hated
+const STANDING_DISCOUNT: f32 = 0.002;
+
+// Kinds of trade goods; every item counts as one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Goods {
+    Food,
+    Ore,
+    Timber,
+    Herbs,
+    Furs,
+    Crafts,
+    Books,
+    Arms,
+    Trinkets,
+}
+
+impl Goods {
+    pub fn label(&self) -> &str {
+        match self {
+            Goods::Food => "food",
+            Goods::Ore => "ore",
+            Goods::Timber => "timber",
+            Goods::Herbs => "herbs",
+            Goods::Furs => "furs",
+            Goods::Crafts => "crafts",
+            Goods::Books => "books",
+            Goods::Arms => "arms",
+            Goods::Trinkets => "trinkets",
+        }
+    }
+}
+
+impl Industry {
+    // What the settlement has plenty of
+    pub fn supplies(&self) -> &[Goods] {
+        match self {
+            Industry::Farming => &[Goods::Food],
+            Industry::Mining => &[Goods::Ore],
+            Industry::Lumber => &[Goods::Timber],
+            Industry::Fishing => &[Goods::Food],
+            Industry::Trading => &[],
+            Industry::Crafting => &[Goods::Crafts, Goods::Arms],
+            Industry::Foraging => &[Goods::Herbs],
+            Industry::Hunting => &[Goods::Furs],
+            Industry::Research => &[Goods::Books],
+        }
+    }
+
+    // What the settlement is short of
+    pub fn demands(&self) -> &[Goods] {
+        match self {
+            Industry::Farming => &[Goods::Crafts, Goods::Ore],
+            Industry::Mining => &[Goods::Food, Goods::Timber],
+            Industry::Lumber => &[Goods::Food, Goods::Crafts],
+            Industry::Fishing => &[Goods::Timber, Goods::Crafts],
+            Industry::Trading => &[Goods::Trinkets],
+            Industry::Crafting => &[Goods::Ore, Goods::Timber],
+            Industry::Foraging => &[Goods::Arms, Goods::Crafts],
+            Industry::Hunting => &[Goods::Arms, Goods::Food],
+            Industry::Research => &[Goods::Herbs, Goods::Trinkets],
+        }
+    }
+}
+
+// The prices of one settlement. They depend on its industry and state, and
+// drift over time in a way that is the same on every visit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Market {
+    seed: u64,
+    industry: Industry,
+    state: LocationState,
+    // The player's standing with the settlement's faction
+    standing: i32,
+}
+
+impl Market {
+    pub fn new(world_seed: u64, position: Position, location: &Location) -> Self {
+        Self {
+            seed: location_seed(world_seed, &[position.x, position.y, 0x6d61726b]),
+            industry: location.industry,
+            state: location.state,
+            standing: 0,
+        }
+    }
+
+    // The same market, for a customer of the given standing
+    pub fn with_standing(self, standing: i32) -> Self {
+        Self { standing, ..self }
+    }
+
+    // What a service usually priced at `price` costs the player here
+    pub fn haggle(&self, price: i32) -> i32 {
+        if price == 0 {
+            return 0;
+        }
+        (price as f32 * (1.0 - self.standing as f32 * STANDING_DISCOUNT)).round().max(1.0) as i32
+    }
+
+    // Multiplier on the usual value of `goods` at the time of `clock`
+    pub fn factor(&self, goods: Goods, clock: &GameClock) -> f32 {
+        let mut factor = if self.industry.supplies().contains(&goods) {
+            SUPPLY_FACTOR
+        } else if self.industry.demands().contains(&goods) {
+            DEMAND_FACTOR
+        } else {
+            1.0
+        };
+        if self.state == LocationState::Struggling {
+            factor *= STRUGGLING_FACTOR;
+        }
+        factor * (1.0 + self.drift(goods, clock))
+    }
+
+    // A random offset per kind of goods and day, blended smoothly between
+    // one day and the next
+    fn drift(&self, goods: Goods, clock: &GameClock) -> f32 {
+        let days = clock.days();
+        let day = days.floor();
+        let offset = |day: f64| {
+            let mut rng = StdRng::seed_from_u64(location_seed(self.seed, &[goods as usize, day as usize]));
+            rng.gen_range(-DRIFT..=DRIFT)
+        };
+        let blend = (days - day) as f32;
+        offset(day) * (1.0 - blend) + offset(day + 1.0) * blend
+    }
+
+    // What the market asks for `item`
+    pub fn price(&self, item: &Item, clock: &GameClock) -> i32 {
+        let value = item.value() as f32;
+        let price = match item.goods() {
+            Some(goods) => (value * self.factor(goods, clock)).round().max(1.0) as i32,
+            None => item.value(),
+        };
+        self.haggle(price)
+    }
+
+    // What the market pays for `item`; less for worn gear, and always less
+    // than it asks, so buying and selling back never pays
+    pub fn offer(&self, item: &Item, clock: &GameClock) -> i32 {
+        let condition = item.condition as f32 / MAX_CONDITION as f32;
+        let respect = 1.0 + self.standing as f32 * STANDING_DISCOUNT;
+        let usual = self.with_standing(0).price(item, clock) as f32;
+        let offer = (usual * SELL_SHARE * condition * respect).floor() as i32;
+        offer.min(self.price(item, clock) - 1).max(0)
+    }
+
+    // Items for sale: everything of the kinds the settlement makes, plus
+    // `wares` from elsewhere
+    pub fn stock(&self, wares: &[String]) -> Vec<Item> {
+        let mut stock: Vec<Item> = items()
+            .iter()
+            .filter(|def| def.goods.is_some_and(|goods| self.industry.supplies().contains(&goods)))
+            .map(|def| Item::new(&def.name))
+            .collect();
+        for name in wares {
+            if item_def(name).is_some() && !stock.iter().any(|item| item.name == *name) {
+                stock.push(Item::new(name));
+            }
+        }
+        stock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::location::{Governance, Species};
+    use crate::systems::reputation::MAX_STANDING;
+
+    fn town(industry: Industry, state: LocationState) -> Location {
+        Location {
+            name: "Testford".to_string(),
+            species: Species::Human,
+            governance: Governance::Council,
+            state,
+            size: 100,
+            industry,
+            searched: false,
+            prosperity: 0,
+            revealed: true,
+        }
+    }
+
+    #[test]
+    fn mining_towns_sell_ore_cheaply_and_buy_food_dearly() {
+        let clock = GameClock::default();
+        let here = Position::new(3, 4);
+        let mine = Market::new(1, here, &town(Industry::Mining, LocationState::Thriving));
+        let farm = Market::new(1, here, &town(Industry::Farming, LocationState::Thriving));
+        let (ore, grain) = (Item::new("Iron ore"), Item::new("Sack of grain"));
+
+        assert!(mine.price(&ore, &clock) < farm.price(&ore, &clock));
+        assert!(mine.offer(&grain, &clock) > farm.offer(&grain, &clock));
+        assert!(mine.stock(&[]).iter().any(|item| item.name == "Iron ore"));
+        assert!(!farm.stock(&[]).iter().any(|item| item.name == "Iron ore"));
+    }
+
+    #[test]
+    fn struggling_towns_pay_more() {
+        let clock = GameClock::default();
+        let here = Position::new(3, 4);
+        let thriving = Market::new(1, here, &town(Industry::Hunting, LocationState::Thriving));
+        let struggling = Market::new(1, here, &town(Industry::Hunting, LocationState::Struggling));
+        let pelts = Item::new("Pelts");
+        assert!(struggling.offer(&pelts, &clock) > thriving.offer(&pelts, &clock));
+    }
+
+    #[test]
+    fn friends_get_better_deals() {
+        let clock = GameClock::default();
+        let market = Market::new(1, Position::new(3, 4), &town(Industry::Trading, LocationState::Thriving));
+        let (friend, foe) = (market.with_standing(100), market.with_standing(-100));
+        let ring = Item::new("Silver ring");
+        assert!(friend.price(&ring, &clock) < market.price(&ring, &clock));
+        assert!(foe.price(&ring, &clock) > market.price(&ring, &clock));
+        assert!(friend.offer(&ring, &clock) > foe.offer(&ring, &clock));
+        assert_eq!(friend.haggle(10), 8);
+    }
+
+    #[test]
+    fn nothing_sells_back_for_what_it_cost() {
+        let clock = GameClock::default();
+        let town = town(Industry::Trading, LocationState::Struggling);
+        let market = Market::new(1, Position::new(3, 4), &town).with_standing(MAX_STANDING);
+        for def in items() {
+            let item = Item::new(&def.name);
+            assert!(market.offer(&item, &clock) < market.price(&item, &clock), "{} pays for itself", def.name);
+        }
+    }
+
+    #[test]
+    fn prices_drift_over_time() {
+        let market = Market::new(1, Position::new(3, 4), &town(Industry::Trading, LocationState::Thriving));
+        let mut clock = GameClock::default();
+        let mut factors = Vec::new();
+        for _ in 0..10 {
+            let factor = market.factor(Goods::Ore, &clock);
+            assert!((1.0 - DRIFT..=1.0 + DRIFT).contains(&factor));
+            factors.push(factor);
+            clock.advance_hours(24);
+        }
+        assert!(factors.windows(2).any(|pair| pair[0] != pair[1]));
+        // The same day always brings the same prices
+        assert_eq!(market.factor(Goods::Ore, &clock), market.factor(Goods::Ore, &clock));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::systems::item::Item;
+use serde::{Deserialize, Serialize};
+
+// What kind of place on the body an item is worn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Slot {
+    Weapon,
+    Offhand,
+    Head,
+    Body,
+    Feet,
+    Ring,
+    Amulet,
+}
+
+impl Slot {
+    // For messages like "Bears can't wear footwear."
+    pub fn plural(&self) -> &str {
+        match self {
+            Slot::Weapon => "weapons",
+            Slot::Offhand => "shields or off-hand items",
+            Slot::Head => "headgear",
+            Slot::Body => "armour",
+            Slot::Feet => "footwear",
+            Slot::Ring => "rings",
+            Slot::Amulet => "amulets",
+        }
+    }
+}
+
+// A place to wear one item. There are two for rings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EquipSlot {
+    Weapon,
+    Offhand,
+    Head,
+    Body,
+    Feet,
+    LeftRing,
+    RightRing,
+    Amulet,
+}
+
+pub const EQUIP_SLOTS: [EquipSlot; 8] = [
+    EquipSlot::Weapon,
+    EquipSlot::Offhand,
+    EquipSlot::Head,
+    EquipSlot::Body,
+    EquipSlot::Feet,
+    EquipSlot::LeftRing,
+    EquipSlot::RightRing,
+    EquipSlot::Amulet,
+];
+
+impl EquipSlot {
+    pub fn slot(&self) -> Slot {
+        match self {
+            EquipSlot::Weapon => Slot::Weapon,
+            EquipSlot::Offhand => Slot::Offhand,
+            EquipSlot::Head => Slot::Head,
+            EquipSlot::Body => Slot::Body,
+            EquipSlot::Feet => Slot::Feet,
+            EquipSlot::LeftRing | EquipSlot::RightRing => Slot::Ring,
+            EquipSlot::Amulet => Slot::Amulet,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            EquipSlot::Weapon => "Weapon",
+            EquipSlot::Offhand => "Off hand",
+            EquipSlot::Head => "Head",
+            EquipSlot::Body => "Body",
+            EquipSlot::Feet => "Feet",
+            EquipSlot::LeftRing => "Left ring",
+            EquipSlot::RightRing => "Right ring",
+            EquipSlot::Amulet => "Amulet",
+        }
+    }
+}
+
+// What a character is wearing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Equipment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weapon: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offhand: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feet: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    left_ring: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    right_ring: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amulet: Option<Item>,
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipSlot) -> Option<&Item> {
+        self.slot(slot).as_ref()
+    }
+
+    fn slot(&self, slot: EquipSlot) -> &Option<Item> {
+        match slot {
+            EquipSlot::Weapon => &self.weapon,
+            EquipSlot::Offhand => &self.offhand,
+            EquipSlot::Head => &self.head,
+            EquipSlot::Body => &self.body,
+            EquipSlot::Feet => &self.feet,
+            EquipSlot::LeftRing => &self.left_ring,
+            EquipSlot::RightRing => &self.right_ring,
+            EquipSlot::Amulet => &self.amulet,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: EquipSlot) -> &mut Option<Item> {
+        match slot {
+            EquipSlot::Weapon => &mut self.weapon,
+            EquipSlot::Offhand => &mut self.offhand,
+            EquipSlot::Head => &mut self.head,
+            EquipSlot::Body => &mut self.body,
+            EquipSlot::Feet => &mut self.feet,
+            EquipSlot::LeftRing => &mut self.left_ring,
+            EquipSlot::RightRing => &mut self.right_ring,
+            EquipSlot::Amulet => &mut self.amulet,
+        }
+    }
+
+    // Everything being worn
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        EQUIP_SLOTS.iter().filter_map(|&slot| self.get(slot))
+    }
+
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut Item> {
+        [
+            &mut self.weapon,
+            &mut self.offhand,
+            &mut self.head,
+            &mut self.body,
+            &mut self.feet,
+            &mut self.left_ring,
+            &mut self.right_ring,
+            &mut self.amulet,
+        ]
+        .into_iter()
+        .filter_map(Option::as_mut)
+    }
+
+    // Where an item for `slot` goes: the first free place, or the first
+    // place if all are taken
+    pub fn place_for(&self, slot: Slot) -> EquipSlot {
+        let places: Vec<EquipSlot> = EQUIP_SLOTS.into_iter().filter(|place| place.slot() == slot).collect();
+        places.iter().copied().find(|&place| self.get(place).is_none()).unwrap_or(places[0])
+    }
+
+    // Put `item` on, returning what was worn there before
+    pub fn put_on(&mut self, place: EquipSlot, item: Item) -> Option<Item> {
+        self.slot_mut(place).replace(item)
+    }
+
+    pub fn take_off(&mut self, place: EquipSlot) -> Option<Item> {
+        self.slot_mut(place).take()
+    }
+}
@@ -0,0 +1,135 @@
+// Recursive shadowcasting field of view.
+//
+// Everything here works in offsets relative to the viewer, so the caller
+// decides how an offset maps onto its grid (wrapping around the world, or
+// clipping at the edge of a location map).
+
+// Multipliers that rotate the first octant onto each of the eight octants
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+// Calls `reveal(dx, dy)` for every offset within `radius` that the viewer can
+// see. `blocks(dx, dy)` says whether the tile at that offset stops sight;
+// blocking tiles are still revealed themselves.
+pub fn compute_fov(
+    radius: i32,
+    blocks: &mut dyn FnMut(i32, i32) -> bool,
+    reveal: &mut dyn FnMut(i32, i32),
+) {
+    reveal(0, 0);
+    for &octant in OCTANTS.iter() {
+        cast_light(1, 1.0, 0.0, radius, octant, blocks, reveal);
+    }
+}
+
+fn cast_light(
+    row: i32,
+    mut start: f32,
+    end: f32,
+    radius: i32,
+    (xx, xy, yx, yy): (i32, i32, i32, i32),
+    blocks: &mut dyn FnMut(i32, i32) -> bool,
+    reveal: &mut dyn FnMut(i32, i32),
+) {
+    if start < end {
+        return;
+    }
+
+    let mut new_start = 0.0;
+    for distance in row..=radius {
+        let dy = -distance;
+        let mut blocked = false;
+
+        for dx in -distance..=0 {
+            // Slopes of the left and right edges of this cell
+            let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+            let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+            if start < right_slope {
+                continue;
+            } else if end > left_slope {
+                break;
+            }
+
+            let x = dx * xx + dy * xy;
+            let y = dx * yx + dy * yy;
+            if dx * dx + dy * dy <= radius * radius {
+                reveal(x, y);
+            }
+
+            let opaque = blocks(x, y);
+            if blocked {
+                if opaque {
+                    new_start = right_slope;
+                } else {
+                    blocked = false;
+                    start = new_start;
+                }
+            } else if opaque && distance < radius {
+                // Start of a shadow: scan the lit part beyond it separately
+                blocked = true;
+                cast_light(distance + 1, start, left_slope, radius, (xx, xy, yx, yy), blocks, reveal);
+                new_start = right_slope;
+            }
+        }
+
+        if blocked {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // Offsets seen from the middle of open ground with walls at `walls`
+    fn seen(radius: i32, walls: &[(i32, i32)]) -> HashSet<(i32, i32)> {
+        let mut seen = HashSet::new();
+        compute_fov(radius, &mut |dx, dy| walls.contains(&(dx, dy)), &mut |dx, dy| {
+            seen.insert((dx, dy));
+        });
+        seen
+    }
+
+    #[test]
+    fn open_ground_is_seen_out_to_the_radius() {
+        let seen = seen(4, &[]);
+        for dy in -6..=6 {
+            for dx in -6..=6 {
+                assert_eq!(seen.contains(&(dx, dy)), dx * dx + dy * dy <= 16, "({dx}, {dy})");
+            }
+        }
+    }
+
+    #[test]
+    fn walls_cast_shadows_in_every_direction() {
+        for (wx, wy) in [(2, 0), (-2, 0), (0, 2), (0, -2)] {
+            let seen = seen(6, &[(wx, wy)]);
+            // The wall itself shows, what is right behind it does not
+            assert!(seen.contains(&(wx, wy)));
+            assert!(!seen.contains(&(wx * 2, wy * 2)));
+            assert!(!seen.contains(&(wx * 3, wy * 3)));
+            // and the other way is clear
+            assert!(seen.contains(&(-wx * 2, -wy * 2)));
+        }
+    }
+
+    #[test]
+    fn a_doorway_lets_a_narrow_view_through() {
+        // A wall across x = 2 with a gap at y = 0
+        let wall: Vec<(i32, i32)> = (-6..=6).filter(|&y| y != 0).map(|y| (2, y)).collect();
+        let seen = seen(6, &wall);
+        assert!(seen.contains(&(5, 0)));
+        assert!(!seen.contains(&(5, 4)));
+        assert!(!seen.contains(&(4, -3)));
+    }
+}
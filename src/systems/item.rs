@@ -0,0 +1,117 @@
+use crate::systems::character::StatModifiers;
+use crate::systems::economy::Goods;
+use crate::systems::equipment::Slot;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const ITEM_DATA: &str = include_str!("../../data/items.toml");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemFile {
+    item: Vec<ItemDef>,
+}
+
+static ITEMS: LazyLock<Vec<ItemDef>> = LazyLock::new(|| {
+    let file: ItemFile = toml::from_str(ITEM_DATA).unwrap_or_else(|e| panic!("data/items.toml is invalid: {e}"));
+    file.item
+});
+
+// A kind of item, as described in data/items.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDef {
+    pub name: String,
+    pub description: String,
+    // Usual price in gold
+    pub value: i32,
+    #[serde(default)]
+    pub goods: Option<Goods>,
+    pub slot: Option<Slot>,
+    #[serde(default)]
+    pub stats: StatModifiers,
+}
+
+// Everything in data/items.toml
+pub fn items() -> &'static [ItemDef] {
+    &ITEMS
+}
+
+pub fn item_def(name: &str) -> Option<&'static ItemDef> {
+    ITEMS.iter().find(|def| def.name == name)
+}
+
+// How well kept a new item is; worn gear loses condition in fights and
+// does nothing once it reaches 0
+pub const MAX_CONDITION: u32 = 100;
+
+fn new_condition() -> u32 {
+    MAX_CONDITION
+}
+
+// Something the player carries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    // Where it can be worn, if anywhere
+    #[serde(default)]
+    pub slot: Option<Slot>,
+    // Added to the attributes of whoever wears it
+    #[serde(default)]
+    pub stats: StatModifiers,
+    #[serde(default = "new_condition")]
+    pub condition: u32,
+}
+
+impl Item {
+    // The item called `name` in data/items.toml, or a plain one that can
+    // only be carried
+    pub fn new(name: &str) -> Self {
+        match item_def(name) {
+            Some(def) => Self { name: def.name.clone(), slot: def.slot, stats: def.stats, condition: MAX_CONDITION },
+            None => Self {
+                name: name.to_string(),
+                slot: None,
+                stats: StatModifiers::default(),
+                condition: MAX_CONDITION,
+            },
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        item_def(&self.name).map_or("", |def| def.description.as_str())
+    }
+
+    pub fn value(&self) -> i32 {
+        item_def(&self.name).map_or(0, |def| def.value)
+    }
+
+    pub fn goods(&self) -> Option<Goods> {
+        item_def(&self.name).and_then(|def| def.goods)
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.condition == 0
+    }
+
+    // What wearing it adds; nothing once it is broken
+    pub fn worn_stats(&self) -> StatModifiers {
+        if self.is_broken() { StatModifiers::default() } else { self.stats }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::class::classes;
+
+    #[test]
+    fn starting_equipment_is_in_the_item_list() {
+        for class in classes() {
+            for name in &class.equipment {
+                assert!(item_def(name).is_some(), "{} starts with {name}, which isn't in data/items.toml", class.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use crate::systems::location::{Location, LocationState, Species};
+use crate::systems::position::Position;
+
+// A settlement the player has laid eyes on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub position: Position,
+    pub name: String,
+    pub species: Species,
+    pub state: LocationState,
+    pub description: String,
+    // Order of discovery, starting at 1
+    pub discovered: usize,
+}
+
+// A note the player pinned to a tile on the world map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapMarker {
+    pub position: Position,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JournalSort {
+    Discovered,
+    Name,
+    Species,
+    State,
+    Distance,
+}
+
+impl JournalSort {
+    pub fn next(&self) -> JournalSort {
+        match self {
+            JournalSort::Discovered => JournalSort::Name,
+            JournalSort::Name => JournalSort::Species,
+            JournalSort::Species => JournalSort::State,
+            JournalSort::State => JournalSort::Distance,
+            JournalSort::Distance => JournalSort::Discovered,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            JournalSort::Discovered => "discovery",
+            JournalSort::Name => "name",
+            JournalSort::Species => "species",
+            JournalSort::State => "state",
+            JournalSort::Distance => "distance",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    pub places: Vec<JournalEntry>,
+    pub markers: Vec<MapMarker>,
+}
+
+impl Journal {
+    // Note down a settlement the first time it is seen. Returns true if it was new.
+    pub fn record_place(&mut self, position: Position, location: &Location) -> bool {
+        if self.place_at(&position).is_some() {
+            return false;
+        }
+        self.places.push(JournalEntry {
+            position,
+            name: location.name.clone(),
+            species: location.species,
+            state: location.state,
+            description: location.generate_description(),
+            discovered: self.places.len() + 1,
+        });
+        true
+    }
+
+    // Bring the entry for a place the player changed up to date
+    pub fn update_place(&mut self, position: Position, location: &Location) {
+        if let Some(entry) = self.places.iter_mut().find(|entry| entry.position == position) {
+            entry.state = location.state;
+            entry.description = location.generate_description();
+        }
+    }
+
+    pub fn place_at(&self, position: &Position) -> Option<&JournalEntry> {
+        self.places.iter().find(|entry| entry.position == *position)
+    }
+
+    // Add a marker, replacing any note already on that tile
+    pub fn set_marker(&mut self, position: Position, note: String) {
+        self.remove_marker(&position);
+        self.markers.push(MapMarker { position, note });
+    }
+
+    pub fn remove_marker(&mut self, position: &Position) -> bool {
+        let before = self.markers.len();
+        self.markers.retain(|marker| marker.position != *position);
+        self.markers.len() != before
+    }
+
+    pub fn marker_at(&self, position: &Position) -> Option<&MapMarker> {
+        self.markers.iter().find(|marker| marker.position == *position)
+    }
+
+    // Places ordered for display. `distance` measures how far an entry is from the player.
+    pub fn sorted_places(&self, sort: JournalSort, distance: impl Fn(&Position) -> u32) -> Vec<&JournalEntry> {
+        let mut places: Vec<&JournalEntry> = self.places.iter().collect();
+        match sort {
+            JournalSort::Discovered => places.sort_by_key(|entry| entry.discovered),
+            JournalSort::Name => places.sort_by(|a, b| a.name.cmp(&b.name)),
+            JournalSort::Species => places.sort_by_key(|entry| entry.species.to_string()),
+            JournalSort::State => places.sort_by(|a, b| a.state.to_string().cmp(b.state.to_string())),
+            JournalSort::Distance => places.sort_by_key(|entry| distance(&entry.position)),
+        }
+        places
+    }
+
+    // Markers ordered for display; only name and distance order them differently
+    // from the order they were placed in.
+    pub fn sorted_markers(&self, sort: JournalSort, distance: impl Fn(&Position) -> u32) -> Vec<&MapMarker> {
+        let mut markers: Vec<&MapMarker> = self.markers.iter().collect();
+        match sort {
+            JournalSort::Name => markers.sort_by(|a, b| a.note.cmp(&b.note)),
+            JournalSort::Distance => markers.sort_by_key(|marker| distance(&marker.position)),
+            _ => {}
+        }
+        markers
+    }
+}
@@ -1,5 +1,6 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
-#[derive(Debug,Copy, Clone, PartialEq)]
+#[derive(Debug,Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Species {
     Human,
     Orc,
@@ -10,6 +11,17 @@ pub enum Species {
     Bear,
     Ghost,
 }
+// Species that build settlements of their own, so a new character has a home town to start in
+pub const PLAYABLE_SPECIES: [Species; 7] = [
+    Species::Human,
+    Species::Elf,
+    Species::Cat,
+    Species::Rat,
+    Species::Bee,
+    Species::Bear,
+    Species::Ghost,
+];
+
 impl fmt::Display for Species {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -24,7 +36,7 @@ impl fmt::Display for Species {
         }
     }
 }
-#[derive(Debug,Copy,  Clone, PartialEq)]
+#[derive(Debug,Copy,  Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Governance {
     Monarchy,
     Democracy,
@@ -34,7 +46,7 @@ pub enum Governance {
     Council,
 }
 
-#[derive(Debug,Copy, Clone, PartialEq)]
+#[derive(Debug,Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LocationState {
     Thriving,
     Struggling,
@@ -108,14 +120,38 @@ pub struct Location {
     pub state: LocationState,
     pub size: usize,
     pub industry: Industry,  // Add this field
+    // The player has already picked through the place
+    pub searched: bool,
+    // How well the place is doing, from -100 to 100; see systems::settlement
+    pub prosperity: i32,
+    // Hidden places stay off the map until they show themselves
+    pub revealed: bool,
 
 }
 
 impl Location {
+    // A hidden place the player hasn't been shown yet
+    pub fn concealed(&self) -> bool {
+        self.state == LocationState::Hidden && !self.revealed
+    }
+
     pub fn generate_description(&self) -> String {
+        let state = self.state.to_string();
+        let article = if state.starts_with(['a', 'e', 'i', 'o', 'u']) { "An" } else { "A" };
+        // Empty places only remember who lived there
+        if matches!(self.state, LocationState::Abandoned | LocationState::Ruins) {
+            return format!(
+                "{} {} {} settlement that once belonged to {}s. Nobody lives here any more.",
+                article,
+                state,
+                self.size_description(),
+                self.species
+            );
+        }
         format!(
-            "A {} {} settlement of {}s under {} rule, where {}.",
-            self.state.to_string().to_lowercase(),
+            "{} {} {} settlement of {}s under {} rule, where {}.",
+            article,
+            state,
             self.size_description(),
             self.species,
             self.governance,
@@ -1,5 +1,20 @@
 pub mod character;
+pub mod class;
+pub mod item;
+pub mod equipment;
+pub mod status;
+pub mod services;
+pub mod economy;
+pub mod settlement;
+pub mod reputation;
+pub mod combat;
 pub mod player;
 pub mod position;
 pub mod world;
-pub mod location;
\ No newline at end of file
+pub mod location;
+pub mod species;
+pub mod fov;
+pub mod pathfinding;
+
+pub mod journal;
+pub mod clock;
\ No newline at end of file
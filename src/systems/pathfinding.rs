@@ -0,0 +1,206 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use crate::systems::clock::world_step_time;
+use crate::systems::position::Position;
+use crate::systems::world::World;
+
+// Neighbouring steps considered when searching for a path, diagonals included
+const STEPS: [(i32, i32); 8] = [(0, -1), (0, 1), (-1, 0), (1, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+// Give up on searches that wander through this many tiles without success
+const MAX_EXPANDED: usize = 200_000;
+
+// A* search from `start` to `goal` over passable world tiles, weighted by
+// the time each step takes to walk. The returned path excludes `start` and
+// ends on `goal`.
+pub fn find_path(world: &World, start: Position, goal: Position) -> Option<Vec<Position>> {
+    if world.get_tile(&goal).blocked {
+        return None;
+    }
+    let straight = world_step_time(world.min_move_cost(), false);
+    let diagonal = world_step_time(world.min_move_cost(), true);
+    search(
+        world,
+        start,
+        |pos| *pos == goal,
+        // Octile distance: diagonal steps while both axes are left, then straight ones
+        |pos| {
+            let (dx, dy) = world.offset(pos, &goal);
+            let (short, long) = (dx.unsigned_abs().min(dy.unsigned_abs()), dx.unsigned_abs().max(dy.unsigned_abs()));
+            short as u64 * diagonal + (long - short) as u64 * straight
+        },
+    )
+}
+
+// Cheapest path to the closest tile matching `is_goal` (Dijkstra's search).
+pub fn find_nearest(
+    world: &World,
+    start: Position,
+    is_goal: impl Fn(&Position) -> bool,
+) -> Option<Vec<Position>> {
+    search(world, start, is_goal, |_| 0)
+}
+
+fn search(
+    world: &World,
+    start: Position,
+    is_goal: impl Fn(&Position) -> bool,
+    heuristic: impl Fn(&Position) -> u64,
+) -> Option<Vec<Position>> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Position, Position> = HashMap::new();
+    let mut cost_so_far: HashMap<Position, u64> = HashMap::new();
+
+    open.push(Reverse((heuristic(&start), (start.x, start.y))));
+    cost_so_far.insert(start, 0);
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, (x, y)))) = open.pop() {
+        let current = Position { x, y };
+        if current != start && is_goal(&current) {
+            return Some(reconstruct_path(&came_from, start, current));
+        }
+
+        expanded += 1;
+        if expanded > MAX_EXPANDED {
+            return None;
+        }
+
+        let current_cost = cost_so_far[&current];
+        for (dx, dy) in STEPS {
+            let Some(next) = world.neighbour(&current, dx, dy) else {
+                continue;
+            };
+            let tile = world.get_tile(&next);
+            if tile.blocked {
+                continue;
+            }
+            // No slipping diagonally between two impassable tiles, as when walking
+            let diagonal = dx != 0 && dy != 0;
+            let is_blocked = |dx, dy| world.neighbour(&current, dx, dy).is_none_or(|pos| world.get_tile(&pos).blocked);
+            if diagonal && is_blocked(dx, 0) && is_blocked(0, dy) {
+                continue;
+            }
+
+            let new_cost = current_cost + world_step_time(tile.terrain.move_cost(), diagonal);
+            if cost_so_far.get(&next).is_none_or(|&cost| new_cost < cost) {
+                cost_so_far.insert(next, new_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((new_cost + heuristic(&next), (next.x, next.y))));
+            }
+        }
+    }
+
+    None
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<Position, Position>,
+    start: Position,
+    goal: Position,
+) -> Vec<Position> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&previous) = came_from.get(&current) {
+        if previous == start {
+            break;
+        }
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::world::TerrainType;
+
+    // A world of open plains with nobody in it
+    fn open_world(width: usize, height: usize) -> World {
+        let mut world = World::new(1, width, height);
+        for row in world.tiles.iter_mut() {
+            for tile in row.iter_mut() {
+                tile.terrain = TerrainType::Plains;
+                tile.blocked = false;
+                tile.location = None;
+            }
+        }
+        world
+    }
+
+    fn wall(world: &mut World, x: usize, ys: std::ops::Range<usize>) {
+        for y in ys {
+            world.tiles[y][x].terrain = TerrainType::Water;
+            world.tiles[y][x].blocked = true;
+        }
+    }
+
+    #[test]
+    fn paths_go_round_what_blocks_them() {
+        let mut world = open_world(30, 30);
+        world.wraparound = false;
+        wall(&mut world, 10, 0..20);
+        let (start, goal) = (Position::new(5, 5), Position::new(15, 5));
+        let path = find_path(&world, start, goal).unwrap();
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|pos| !world.get_tile(pos).blocked));
+        // Each step is to a neighbouring tile
+        let mut previous = start;
+        for pos in &path {
+            let (dx, dy) = world.offset(&previous, pos);
+            assert_eq!(dx.abs().max(dy.abs()), 1);
+            previous = *pos;
+        }
+        assert!(path.iter().any(|pos| pos.y >= 20));
+    }
+
+    #[test]
+    fn no_path_to_blocked_or_walled_off_goals() {
+        let mut world = open_world(30, 30);
+        world.wraparound = false;
+        wall(&mut world, 10, 0..30);
+        assert_eq!(find_path(&world, Position::new(5, 5), Position::new(15, 5)), None);
+        assert_eq!(find_path(&world, Position::new(5, 5), Position::new(10, 5)), None);
+        assert_eq!(find_nearest(&world, Position::new(5, 5), |pos| pos.x > 10), None);
+    }
+
+    #[test]
+    fn paths_take_the_short_way_across_the_seam() {
+        let world = open_world(30, 30);
+        let path = find_path(&world, Position::new(1, 5), Position::new(28, 5)).unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0], Position::new(0, 5));
+    }
+
+    #[test]
+    fn roads_are_worth_a_detour() {
+        let mut world = open_world(30, 30);
+        world.wraparound = false;
+        // A road one row off the straight line
+        for x in 2..=20 {
+            world.tiles[6][x].terrain = TerrainType::Road;
+        }
+        let path = find_path(&world, Position::new(2, 5), Position::new(20, 5)).unwrap();
+        let on_road = path.iter().filter(|pos| world.get_tile(pos).terrain == TerrainType::Road).count();
+        assert!(on_road > 10);
+    }
+
+    #[test]
+    fn paths_cut_corners_but_not_between_walls() {
+        let mut world = open_world(30, 30);
+        world.wraparound = false;
+        let path = find_path(&world, Position::new(5, 5), Position::new(10, 10)).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0], Position::new(6, 6));
+
+        // Two walls meeting at a corner leave no gap to squeeze through,
+        // so the way round is longer
+        world.tiles[5][6].blocked = true;
+        world.tiles[6][5].blocked = true;
+        let path = find_path(&world, Position::new(5, 5), Position::new(10, 10)).unwrap();
+        assert!(!path.contains(&Position::new(6, 6)));
+        assert!(path.len() > 5);
+    }
+}
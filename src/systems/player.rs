@@ -2,9 +2,16 @@
 use crate::systems::{
     character::Character,
     position::Position,
+    reputation::Reputation,
 };
+// Coin a new character sets out with
+pub const STARTING_GOLD: i32 = 25;
+
 pub struct Player {
     pub character: Character,
+    pub gold: i32,
+    // How the factions of the world regard the player
+    pub reputation: Reputation,
     pub world_pos: Position,
     pub local_pos: Position,
 }
@@ -13,19 +20,26 @@ impl Player {
     pub fn new(character: Character, world_x: usize, world_y: usize) -> Self {
         Self {
             character,
+            gold: STARTING_GOLD,
+            reputation: Reputation::default(),
             world_pos: Position { x: world_x, y: world_y },
             local_pos: Position { x: 0, y: 0 },  // Will be set when entering location
         }
     }
 
-    pub fn create_random(world_x: usize, world_y: usize) -> Self {
-        Self::new(Character::create_random(), world_x, world_y)
-    }
-
     pub fn enter_location(&mut self, spawn_x: usize, spawn_y: usize) {
         self.local_pos = Position { x: spawn_x, y: spawn_y };
     }
 
+    // Pay `price` if the purse allows it
+    pub fn spend_gold(&mut self, price: i32) -> bool {
+        if self.gold < price {
+            return false;
+        }
+        self.gold -= price;
+        true
+    }
+
     pub fn exit_location(&mut self) {
         // Return to world map position
         // local_pos will be updated next time we enter a location
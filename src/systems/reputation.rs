@@ -0,0 +1,186 @@
+use crate::systems::location::{Governance, Location, Species};
+use crate::systems::species::Attitude;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const MIN_STANDING: i32 = -100;
+pub const MAX_STANDING: i32 = 100;
+// Settlements of the same species under other rule hear of what the player
+// did and take this share of it to heart
+const KIN_SHARE: i32 = 4;
+
+// The people of every settlement of one species under one kind of rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Faction {
+    pub species: Species,
+    pub governance: Governance,
+}
+
+impl Faction {
+    pub fn of(location: &Location) -> Self {
+        Self { species: location.species, governance: location.governance }
+    }
+}
+
+// e.g. "Monarchic Humans"
+impl fmt::Display for Faction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}s", self.governance, self.species)
+    }
+}
+
+// How a faction regards the player, in bands of reputation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Standing {
+    Hated,
+    Disliked,
+    Neutral,
+    Liked,
+    Honoured,
+}
+
+impl Standing {
+    pub fn from_value(value: i32) -> Self {
+        match value {
+            ..=-50 => Standing::Hated,
+            -49..=-10 => Standing::Disliked,
+            -9..=9 => Standing::Neutral,
+            10..=49 => Standing::Liked,
+            _ => Standing::Honoured,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Standing::Hated => "hated",
+            Standing::Disliked => "disliked",
+            Standing::Neutral => "neutral",
+            Standing::Liked => "liked",
+            Standing::Honoured => "honoured",
+        }
+    }
+
+    // How far this standing moves a settlement's usual attitude
+    // towards the player's species: positive is warmer
+    fn shift(&self) -> i32 {
+        match self {
+            Standing::Hated => -3,
+            Standing::Disliked => -1,
+            Standing::Neutral => 0,
+            Standing::Liked => 1,
+            Standing::Honoured => 3,
+        }
+    }
+}
+
+// How a settlement treats the player: how its species regards theirs,
+// warmed or cooled by the player's standing with its faction
+pub fn disposition(attitude: Attitude, standing: Standing) -> Attitude {
+    let warmth = match attitude {
+        Attitude::Hostile => 0,
+        Attitude::Wary => 1,
+        Attitude::Neutral => 2,
+        Attitude::Friendly => 3,
+    };
+    match (warmth + standing.shift()).clamp(0, 3) {
+        0 => Attitude::Hostile,
+        1 => Attitude::Wary,
+        2 => Attitude::Neutral,
+        _ => Attitude::Friendly,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionStanding {
+    pub faction: Faction,
+    pub value: i32,
+}
+
+// The player's standing with every faction that has an opinion of them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reputation {
+    factions: Vec<FactionStanding>,
+}
+
+impl Reputation {
+    pub fn get(&self, faction: Faction) -> i32 {
+        self.factions
+            .iter()
+            .find(|entry| entry.faction == faction)
+            .map_or(0, |entry| entry.value)
+    }
+
+    pub fn standing(&self, faction: Faction) -> Standing {
+        Standing::from_value(self.get(faction))
+    }
+
+    // Raise or lower the player's standing with `faction` by `amount`; its
+    // kin under other rule move by a share of it. Returns a note when the
+    // faction's view of the player changes, e.g. "The Monarchic Humans now
+    // regard you as liked."
+    pub fn change(&mut self, faction: Faction, amount: i32) -> Option<String> {
+        let before = self.standing(faction);
+        self.add(faction, amount);
+        let share = amount / KIN_SHARE;
+        for &governance in faction.species.governances() {
+            let kin = Faction { species: faction.species, governance };
+            if kin != faction && share != 0 {
+                self.add(kin, share);
+            }
+        }
+        let after = self.standing(faction);
+        (after != before).then(|| format!("The {} now regard you as {}.", faction, after.label()))
+    }
+
+    fn add(&mut self, faction: Faction, amount: i32) {
+        let index = match self.factions.iter().position(|entry| entry.faction == faction) {
+            Some(index) => index,
+            None => {
+                self.factions.push(FactionStanding { faction, value: 0 });
+                self.factions.len() - 1
+            }
+        };
+        let entry = &mut self.factions[index];
+        entry.value = (entry.value + amount).clamp(MIN_STANDING, MAX_STANDING);
+    }
+
+    // Every faction with an opinion, best regarded first
+    pub fn factions(&self) -> Vec<&FactionStanding> {
+        let mut factions: Vec<_> = self.factions.iter().filter(|entry| entry.value != 0).collect();
+        factions.sort_by_key(|entry| -entry.value);
+        factions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINGDOM: Faction = Faction { species: Species::Human, governance: Governance::Monarchy };
+    const REPUBLIC: Faction = Faction { species: Species::Human, governance: Governance::Democracy };
+    const HIVE: Faction = Faction { species: Species::Bee, governance: Governance::Hivemind };
+
+    #[test]
+    fn kin_hear_of_deeds_but_other_species_do_not() {
+        let mut reputation = Reputation::default();
+        assert_eq!(reputation.change(KINGDOM, 20), Some("The Monarchic Humans now regard you as liked.".to_string()));
+        assert_eq!(reputation.get(KINGDOM), 20);
+        assert_eq!(reputation.get(REPUBLIC), 5);
+        assert_eq!(reputation.get(Faction { species: Species::Human, governance: Governance::Hivemind }), 0);
+        assert_eq!(reputation.get(HIVE), 0);
+        assert_eq!(reputation.change(KINGDOM, 1), None);
+
+        reputation.change(KINGDOM, 1000);
+        assert_eq!(reputation.get(KINGDOM), MAX_STANDING);
+        assert_eq!(reputation.factions()[0].faction, KINGDOM);
+    }
+
+    #[test]
+    fn standing_warms_or_cools_a_settlement() {
+        assert_eq!(disposition(Attitude::Neutral, Standing::Neutral), Attitude::Neutral);
+        assert_eq!(disposition(Attitude::Neutral, Standing::Liked), Attitude::Friendly);
+        assert_eq!(disposition(Attitude::Hostile, Standing::Liked), Attitude::Wary);
+        assert_eq!(disposition(Attitude::Wary, Standing::Honoured), Attitude::Friendly);
+        assert_eq!(disposition(Attitude::Friendly, Standing::Hated), Attitude::Hostile);
+    }
+}
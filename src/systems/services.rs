@@ -0,0 +1,155 @@
+use crate::generators::location_generator::FeatureType;
+use crate::systems::character::{ATTRIBUTES, Attribute, Character};
+use crate::systems::clock::GameClock;
+use crate::systems::economy::Market;
+use crate::systems::item::Item;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const FEATURE_DATA: &str = include_str!("../../data/features.toml");
+
+// Training stops once an attribute's base value gets this high
+pub const TRAINING_CAP: i32 = 18;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FeatureFile {
+    feature: Vec<FeatureDef>,
+}
+
+static FEATURES: LazyLock<Vec<FeatureDef>> = LazyLock::new(|| {
+    let file: FeatureFile =
+        toml::from_str(FEATURE_DATA).unwrap_or_else(|e| panic!("data/features.toml is invalid: {e}"));
+    file.feature
+});
+
+// What a kind of feature offers visitors, as described in data/features.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureDef {
+    #[serde(rename = "type")]
+    pub feature: FeatureType,
+    pub services: Vec<Service>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Service {
+    // Sells `wares` at local prices
+    Shop { wares: Vec<String> },
+    // Buys and sells: the settlement's own goods plus `wares`
+    Trade { wares: Vec<String> },
+    Heal { price: i32 },
+    Bless { price: i32 },
+    Rest { price: i32 },
+    Rumours { price: i32 },
+    // `price` per 10 points of condition
+    Repair { price: i32 },
+    // `price` times the attribute's base value
+    Train { price: i32, hours: u64 },
+}
+
+// What taking up an offer does
+#[derive(Debug, Clone, PartialEq)]
+pub enum Deal {
+    Buy(String),
+    Trade(Vec<String>),
+    Heal,
+    Bless,
+    Rest,
+    Rumours,
+    Repair,
+    Train { attribute: Attribute, hours: u64 },
+}
+
+// One line on the list shown when visiting a feature
+#[derive(Debug, Clone, PartialEq)]
+pub struct Offer {
+    pub label: String,
+    pub price: i32,
+    pub deal: Deal,
+}
+
+impl Service {
+    // The offers this service makes to `character`; prices of repairs and
+    // training depend on them, and all prices on the local market
+    pub fn offers(&self, character: &Character, market: &Market, clock: &GameClock) -> Vec<Offer> {
+        let offer = |label: &str, price, deal| Offer { label: label.to_string(), price, deal };
+        match self {
+            Service::Shop { wares } => wares
+                .iter()
+                .map(|name| {
+                    let item = Item::new(name);
+                    offer(&format!("Buy {}", item.name), market.price(&item, clock), Deal::Buy(item.name))
+                })
+                .collect(),
+            Service::Trade { wares } => vec![offer("Trade at the market", 0, Deal::Trade(wares.clone()))],
+            Service::Heal { price } => vec![offer("Have your wounds tended", market.haggle(*price), Deal::Heal)],
+            Service::Bless { price } => vec![offer("Make an offering and pray", market.haggle(*price), Deal::Bless)],
+            Service::Rest { price } => vec![offer("Take a room for the night", market.haggle(*price), Deal::Rest)],
+            Service::Rumours { price } => vec![offer("Buy a round and listen for rumours", market.haggle(*price), Deal::Rumours)],
+            Service::Repair { price } => {
+                let price = market.haggle(price * character.gear_damage().div_ceil(10) as i32);
+                vec![offer("Repair your gear", price, Deal::Repair)]
+            }
+            Service::Train { price, hours } => ATTRIBUTES
+                .iter()
+                .filter(|&&attribute| attribute != Attribute::Luck)
+                .map(|&attribute| {
+                    let base = character.attributes.get(attribute);
+                    offer(
+                        &format!("Train {} ({} hours)", attribute.label(), hours),
+                        market.haggle(price * base),
+                        Deal::Train { attribute, hours: *hours },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn feature_services(feature: FeatureType) -> &'static [Service] {
+    FEATURES
+        .iter()
+        .find(|def| def.feature == feature)
+        .map_or(&[], |def| def.services.as_slice())
+}
+
+// Everything a feature of kind `feature` offers, in the order of its services
+pub fn feature_offers(feature: FeatureType, character: &Character, market: &Market, clock: &GameClock) -> Vec<Offer> {
+    feature_services(feature)
+        .iter()
+        .flat_map(|service| service.offers(character, market, clock))
+        .collect()
+}
+
+// What a market sells besides the goods of its settlement
+pub fn market_wares() -> &'static [String] {
+    feature_services(FeatureType::Market)
+        .iter()
+        .find_map(|service| match service {
+            Service::Trade { wares } => Some(wares.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::item::item_def;
+
+    #[test]
+    fn wares_are_in_the_item_list() {
+        for def in FEATURES.iter() {
+            for service in &def.services {
+                if let Service::Shop { wares } | Service::Trade { wares } = service {
+                    for name in wares {
+                        assert!(item_def(name).is_some(), "{:?} sells {name}, which isn't in data/items.toml", def.feature);
+                    }
+                }
+            }
+        }
+    }
+}
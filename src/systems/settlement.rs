@@ -0,0 +1,312 @@
+use crate::generators::location_generator::location_seed;
+use crate::systems::location::{Industry, Location, LocationState, Species};
+use crate::systems::position::Position;
+use crate::systems::world::{TerrainType, World};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Prosperity runs from ruinous to booming
+pub const MIN_PROSPERITY: i32 = -100;
+pub const MAX_PROSPERITY: i32 = 100;
+// Settlements this many steps apart along the roads trade with each other
+const TRADE_RANGE: usize = 16;
+// Daily prosperity from each trade partner, up to a few of them; merchant
+// towns make twice as much
+const TRADE_INCOME: i32 = 2;
+const MAX_PARTNERS: i32 = 3;
+// Daily loss for a settlement nobody trades with
+const ISOLATION: i32 = 1;
+// Every this many people cost a point of prosperity a day
+const CROWDING: usize = 100;
+// Good and bad days, either way
+const FORTUNE: i32 = 2;
+// Population changes by size * prosperity / GROWTH_DIVISOR a day
+const GROWTH_DIVISOR: i32 = 2000;
+// Thresholds between thriving, struggling and abandoned
+const THRIVING_ABOVE: i32 = 40;
+const STRUGGLING_BELOW: i32 = 0;
+const ABANDONED_BELOW: i32 = -60;
+const MIN_POPULATION: usize = 10;
+// One in this many days an abandoned place crumbles into ruins
+const CRUMBLE_DAYS: u32 = 60;
+// One in this many days settlers from a thriving neighbour of the same
+// species move into an abandoned place, or rebuild ruins
+const RESETTLE_DAYS: u32 = 30;
+const REBUILD_DAYS: u32 = 90;
+const SETTLERS: usize = 15;
+
+// How well a settlement is doing when the world is made
+pub fn starting_prosperity(state: LocationState) -> i32 {
+    match state {
+        LocationState::Thriving => 60,
+        LocationState::Struggling => -20,
+        _ => 0,
+    }
+}
+
+// What a settlement looked like at the start of the day, so every
+// settlement sees its neighbours as they were before any of them changed
+struct Site {
+    position: Position,
+    species: Species,
+    industry: Industry,
+    state: LocationState,
+}
+
+impl Site {
+    fn inhabited(&self) -> bool {
+        matches!(self.state, LocationState::Thriving | LocationState::Struggling)
+    }
+}
+
+// Let one day pass for every settlement but the one at `skip`, where the
+// player is. Cursed, sacred and hidden places keep to themselves. Returns
+// the settlements whose state changed.
+pub fn simulate_day(world: &mut World, day: u64, skip: Option<Position>) -> Vec<Position> {
+    let sites: Vec<Site> = world
+        .tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter().enumerate().filter_map(move |(x, tile)| {
+                tile.location.as_ref().map(|location| Site {
+                    position: Position::new(x, y),
+                    species: location.species,
+                    industry: location.industry,
+                    state: location.state,
+                })
+            })
+        })
+        .collect();
+    let index: HashMap<Position, usize> = sites.iter().enumerate().map(|(i, site)| (site.position, i)).collect();
+
+    let mut changed = Vec::new();
+    for site in &sites {
+        if Some(site.position) == skip {
+            continue;
+        }
+        let neighbours: Vec<&Site> = along_the_roads(world, site.position)
+            .into_iter()
+            .filter_map(|position| index.get(&position).map(|&i| &sites[i]))
+            .collect();
+        let mut rng = StdRng::seed_from_u64(location_seed(world.seed, &[site.position.x, site.position.y, day as usize]));
+        let location = world.tiles[site.position.y][site.position.x]
+            .location
+            .as_mut()
+            .expect("sites are collected from tiles with a location");
+        let before = location.state;
+        match location.state {
+            LocationState::Thriving | LocationState::Struggling => {
+                let partners = neighbours.iter().filter(|other| trades_with(site, other)).count() as i32;
+                live_a_day(location, partners, &mut rng);
+            }
+            LocationState::Abandoned | LocationState::Ruins => {
+                let settlers = neighbours
+                    .iter()
+                    .any(|other| other.species == site.species && other.state == LocationState::Thriving);
+                decay_a_day(location, settlers, &mut rng);
+            }
+            LocationState::Cursed | LocationState::Sacred | LocationState::Hidden => {}
+        }
+        if location.state != before {
+            changed.push(site.position);
+        }
+    }
+    changed
+}
+
+// The other settlements within TRADE_RANGE steps of `start` along the
+// roads. Settlements on the way are passed through, like crossroads.
+fn along_the_roads(world: &World, start: Position) -> Vec<Position> {
+    let mut reached = Vec::new();
+    let mut visited = HashSet::from([start]);
+    let mut frontier = VecDeque::from([(start, 0)]);
+    while let Some((current, steps)) = frontier.pop_front() {
+        if steps == TRADE_RANGE {
+            continue;
+        }
+        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+            let Some(next) = world.neighbour(&current, dx, dy) else {
+                continue;
+            };
+            let tile = world.get_tile(&next);
+            if !visited.insert(next) || (tile.terrain != TerrainType::Road && tile.location.is_none()) {
+                continue;
+            }
+            if tile.location.is_some() {
+                reached.push(next);
+            }
+            frontier.push_back((next, steps + 1));
+        }
+    }
+    reached
+}
+
+// Inhabited neighbours trade when one makes what the other lacks; merchant
+// towns trade with anyone
+fn trades_with(site: &Site, other: &Site) -> bool {
+    if !other.inhabited() {
+        return false;
+    }
+    let (wants, makes) = (site.industry.demands(), site.industry.supplies());
+    site.industry == Industry::Trading
+        || other.industry == Industry::Trading
+        || other.industry.supplies().iter().any(|goods| wants.contains(goods))
+        || other.industry.demands().iter().any(|goods| makes.contains(goods))
+}
+
+fn live_a_day(location: &mut Location, partners: i32, rng: &mut StdRng) {
+    let income = match partners.min(MAX_PARTNERS) {
+        0 => -ISOLATION,
+        partners if location.industry == Industry::Trading => partners * TRADE_INCOME * 2,
+        partners => partners * TRADE_INCOME,
+    };
+    let crowding = (location.size / CROWDING) as i32;
+    let fortune = rng.gen_range(-FORTUNE..=FORTUNE);
+    location.prosperity = (location.prosperity + income - crowding + fortune).clamp(MIN_PROSPERITY, MAX_PROSPERITY);
+
+    // Good times draw people in, hard times drive them away
+    let growth = match location.size as i32 * location.prosperity / GROWTH_DIVISOR {
+        0 => location.prosperity.signum(),
+        growth => growth,
+    };
+    location.size = (location.size as i32 + growth).max(0) as usize;
+
+    location.state = match location.state {
+        _ if location.prosperity < ABANDONED_BELOW || location.size < MIN_POPULATION => LocationState::Abandoned,
+        LocationState::Thriving if location.prosperity < STRUGGLING_BELOW => LocationState::Struggling,
+        LocationState::Struggling if location.prosperity > THRIVING_ABOVE => LocationState::Thriving,
+        state => state,
+    };
+    if location.state == LocationState::Abandoned {
+        location.prosperity = 0;
+    }
+}
+
+fn decay_a_day(location: &mut Location, settlers: bool, rng: &mut StdRng) {
+    let rebuild_days = if location.state == LocationState::Ruins { REBUILD_DAYS } else { RESETTLE_DAYS };
+    if settlers && rng.gen_ratio(1, rebuild_days) {
+        // Newcomers start small and poor, with nothing left to scavenge
+        location.state = LocationState::Struggling;
+        location.size = SETTLERS;
+        location.prosperity = starting_prosperity(LocationState::Struggling);
+        location.searched = true;
+    } else if location.state == LocationState::Abandoned && rng.gen_ratio(1, CRUMBLE_DAYS) {
+        // A ruin has more to pick through than the empty houses did
+        location.state = LocationState::Ruins;
+        location.searched = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::location::Governance;
+
+    fn town(industry: Industry, state: LocationState, size: usize) -> Location {
+        Location {
+            name: "Testford".to_string(),
+            species: Species::Human,
+            governance: Governance::Council,
+            state,
+            size,
+            industry,
+            searched: false,
+            prosperity: starting_prosperity(state),
+            revealed: true,
+        }
+    }
+
+    // A world of plains with the given settlements on it and no roads
+    fn world_with(towns: Vec<(Position, Location)>) -> World {
+        let mut world = World::new(1, 32, 32);
+        for row in world.tiles.iter_mut() {
+            for tile in row.iter_mut() {
+                tile.terrain = TerrainType::Plains;
+                tile.blocked = false;
+                tile.location = None;
+            }
+        }
+        for (position, location) in towns {
+            world.tiles[position.y][position.x].location = Some(location);
+        }
+        world
+    }
+
+    // A road along row `y` from column `from` to `to`
+    fn road(world: &mut World, y: usize, from: usize, to: usize) {
+        for x in from..=to {
+            world.tiles[y][x].terrain = TerrainType::Road;
+        }
+    }
+
+    fn location(world: &World, position: Position) -> &Location {
+        world.get_tile(&position).location.as_ref().unwrap()
+    }
+
+    #[test]
+    fn trade_makes_towns_prosper_and_grow() {
+        let (mine, farm, hermits) = (Position::new(4, 4), Position::new(8, 4), Position::new(24, 24));
+        let mut world = world_with(vec![
+            (mine, town(Industry::Mining, LocationState::Struggling, 40)),
+            (farm, town(Industry::Farming, LocationState::Struggling, 40)),
+            (hermits, town(Industry::Mining, LocationState::Struggling, 40)),
+        ]);
+        road(&mut world, 4, 5, 7);
+        for day in 0..60 {
+            simulate_day(&mut world, day, None);
+        }
+        assert_eq!(location(&world, mine).state, LocationState::Thriving);
+        assert!(location(&world, mine).size > 40);
+        assert_eq!(location(&world, hermits).state, LocationState::Abandoned);
+    }
+
+    #[test]
+    fn only_towns_on_the_road_trade() {
+        // The second farm is as close to the mine, but off the road
+        let (mine, farm, off_road) = (Position::new(4, 4), Position::new(12, 4), Position::new(4, 7));
+        let mut world = world_with(vec![
+            (mine, town(Industry::Mining, LocationState::Struggling, 40)),
+            (farm, town(Industry::Farming, LocationState::Struggling, 40)),
+            (off_road, town(Industry::Farming, LocationState::Struggling, 40)),
+        ]);
+        road(&mut world, 4, 5, 11);
+        let start = location(&world, mine).prosperity;
+        for day in 0..20 {
+            simulate_day(&mut world, day, None);
+        }
+        assert!(location(&world, mine).prosperity > start);
+        assert!(location(&world, farm).prosperity > start);
+        assert!(location(&world, off_road).prosperity < start);
+        assert_eq!(along_the_roads(&world, mine), vec![farm]);
+        assert!(along_the_roads(&world, off_road).is_empty());
+    }
+
+    #[test]
+    fn lonely_towns_empty_and_crumble() {
+        let here = Position::new(4, 4);
+        let mut world = world_with(vec![(here, town(Industry::Mining, LocationState::Struggling, 12))]);
+        let mut changes = Vec::new();
+        for day in 0..1000 {
+            for position in simulate_day(&mut world, day, None) {
+                changes.push(location(&world, position).state);
+            }
+        }
+        assert_eq!(changes, vec![LocationState::Abandoned, LocationState::Ruins]);
+    }
+
+    #[test]
+    fn settlers_rebuild_near_thriving_towns() {
+        let (ruins, city) = (Position::new(4, 4), Position::new(6, 4));
+        let mut world = world_with(vec![
+            (ruins, town(Industry::Farming, LocationState::Ruins, 20)),
+            (city, town(Industry::Trading, LocationState::Thriving, 90)),
+        ]);
+        road(&mut world, 4, 5, 5);
+        let rebuilt = (0..1000).any(|day| simulate_day(&mut world, day, Some(city)).contains(&ruins));
+        assert!(rebuilt);
+        assert_eq!(location(&world, ruins).state, LocationState::Struggling);
+        assert_eq!(location(&world, ruins).size, SETTLERS);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::systems::character::StatModifiers;
+use crate::systems::equipment::Slot;
+use crate::systems::location::{Governance, Species};
+use crate::systems::world::TerrainType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const SPECIES_DATA: &str = include_str!("../../data/species.toml");
+
+static TRAITS: LazyLock<HashMap<Species, SpeciesTraits>> = LazyLock::new(|| {
+    toml::from_str(SPECIES_DATA).unwrap_or_else(|e| panic!("data/species.toml is invalid: {e}"))
+});
+// For species without an entry
+static NO_TRAITS: LazyLock<SpeciesTraits> = LazyLock::new(SpeciesTraits::default);
+
+// How a settlement receives a visitor of another species
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Attitude {
+    Friendly,
+    #[default]
+    Neutral,
+    Wary,
+    Hostile,
+}
+
+impl fmt::Display for Attitude {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Attitude::Friendly => write!(f, "Friendly"),
+            Attitude::Neutral => write!(f, "Neutral"),
+            Attitude::Wary => write!(f, "Wary"),
+            Attitude::Hostile => write!(f, "Hostile"),
+        }
+    }
+}
+
+// What sets a species apart, as described in data/species.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpeciesTraits {
+    pub description: String,
+    pub stats: StatModifiers,
+    // Travel time per terrain, in percent of the usual
+    pub terrain: HashMap<TerrainType, u64>,
+    // Extra tiles of sight
+    pub vision: i32,
+    pub night_vision: bool,
+    // Equipment slots the species can't use, e.g. bears can't wear boots
+    pub cannot_wear: Vec<Slot>,
+    pub attitudes: HashMap<Species, Attitude>,
+    pub governances: Vec<Governance>,
+}
+
+impl SpeciesTraits {
+    // How long crossing `terrain` takes this species, given the usual time
+    pub fn travel_time(&self, terrain: TerrainType, seconds: u64) -> u64 {
+        match self.terrain.get(&terrain) {
+            Some(percent) => seconds * percent / 100,
+            None => seconds,
+        }
+    }
+}
+
+impl Species {
+    pub fn traits(&self) -> &'static SpeciesTraits {
+        TRAITS.get(self).unwrap_or(&NO_TRAITS)
+    }
+
+    // How this species' settlements receive a visitor
+    pub fn attitude_towards(&self, visitor: Species) -> Attitude {
+        if *self == visitor {
+            return Attitude::Friendly;
+        }
+        self.traits().attitudes.get(&visitor).copied().unwrap_or_default()
+    }
+
+    // The ways this species rules its settlements
+    pub fn governances(&self) -> &'static [Governance] {
+        &self.traits().governances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::location::PLAYABLE_SPECIES;
+
+    #[test]
+    fn every_playable_species_has_traits() {
+        for species in PLAYABLE_SPECIES {
+            assert!(TRAITS.contains_key(&species), "no traits for {species} in data/species.toml");
+        }
+    }
+
+    #[test]
+    fn every_species_has_a_way_to_rule_itself() {
+        for species in PLAYABLE_SPECIES.into_iter().chain([Species::Orc]) {
+            assert!(!species.governances().is_empty(), "no governances for {species} in data/species.toml");
+        }
+        assert_eq!(Species::Bee.governances(), [Governance::Hivemind]);
+    }
+
+    #[test]
+    fn terrain_affinities() {
+        assert_eq!(Species::Elf.traits().travel_time(TerrainType::Forest, 100), 50);
+        assert_eq!(Species::Elf.traits().travel_time(TerrainType::Plains, 100), 100);
+        // Snow is no worse than open plains for a bear
+        assert_eq!(Species::Bear.traits().travel_time(TerrainType::Snow, 4 * 60), 2 * 60);
+    }
+
+    #[test]
+    fn everyone_welcomes_their_own_kind() {
+        assert_eq!(Species::Orc.attitude_towards(Species::Orc), Attitude::Friendly);
+        assert_eq!(Species::Human.attitude_towards(Species::Orc), Attitude::Hostile);
+        assert_eq!(Species::Bee.attitude_towards(Species::Human), Attitude::Neutral);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::systems::character::StatModifiers;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+// Built into the binary so the game runs from anywhere
+const EFFECT_DATA: &str = include_str!("../../data/effects.toml");
+
+// Effects the game applies by name
+pub const BLESSED: &str = "Blessed";
+pub const CURSED: &str = "Cursed";
+pub const EXHAUSTED: &str = "Exhausted";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EffectFile {
+    effect: Vec<EffectDef>,
+}
+
+static EFFECTS: LazyLock<Vec<EffectDef>> = LazyLock::new(|| {
+    let file: EffectFile =
+        toml::from_str(EFFECT_DATA).unwrap_or_else(|e| panic!("data/effects.toml is invalid: {e}"));
+    file.effect
+});
+
+// What happens when an effect is applied while it still lasts
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stacking {
+    #[default]
+    Refresh,
+    Extend,
+    Stack,
+    Ignore,
+}
+
+// A kind of status effect, as described in data/effects.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EffectDef {
+    pub name: String,
+    pub description: String,
+    pub duration: u32,
+    // Gained every turn per stack, or lost if negative
+    #[serde(default)]
+    pub health: i32,
+    #[serde(default)]
+    pub stats: StatModifiers,
+    #[serde(default)]
+    pub stacking: Stacking,
+    #[serde(default = "one_stack")]
+    pub max_stacks: u32,
+    #[serde(default)]
+    pub cured_by_rest: bool,
+}
+
+fn one_stack() -> u32 {
+    1
+}
+
+pub fn effect_def(name: &str) -> Option<&'static EffectDef> {
+    EFFECTS.iter().find(|def| def.name == name)
+}
+
+// An effect on a character
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub name: String,
+    pub turns_left: u32,
+    pub stacks: u32,
+}
+
+impl StatusEffect {
+    pub fn def(&self) -> Option<&'static EffectDef> {
+        effect_def(&self.name)
+    }
+
+    // What it adds to the attributes right now
+    pub fn stats(&self) -> StatModifiers {
+        self.def().map_or(StatModifiers::default(), |def| def.stats * self.stacks as i32)
+    }
+
+    // e.g. "Poisoned x2 (5 turns)"
+    pub fn describe(&self) -> String {
+        if self.stacks > 1 {
+            format!("{} x{} ({} turns)", self.name, self.stacks, self.turns_left)
+        } else {
+            format!("{} ({} turns)", self.name, self.turns_left)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_used_by_the_game_exist() {
+        for name in [BLESSED, CURSED, EXHAUSTED] {
+            assert!(effect_def(name).is_some(), "{name} is missing from data/effects.toml");
+        }
+    }
+}
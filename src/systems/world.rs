@@ -1,7 +1,10 @@
 use crate::generators::world_generator::WorldGenerator;
 use crate::systems::location::{Location, Species};
 use crate::systems::position::Position;
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::systems::fov::compute_fov;
+use serde::Deserialize;
+use std::collections::HashSet;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum TerrainType {
     Water,
     Plains,
@@ -35,19 +38,80 @@ pub struct Tile {
 
 pub struct World {
     pub seed: u64,
+    // The seed the world was generated from, enough to rebuild it when loading a save
+    pub generation_seed: u32,
     pub width: usize,
     pub height: usize,
     pub wraparound: bool,
     pub tiles: TileGrid,
+    // Tiles currently in the player's line of sight
+    pub visible: HashSet<Position>,
+    // Where the player last surveyed from; the wider view lasts until they move
+    pub surveyed_from: Option<Position>,
+}
+
+impl TerrainType {
+    // Relative effort of crossing one tile of this terrain
+    pub fn move_cost(&self) -> u32 {
+        match self {
+            TerrainType::Road => 1,
+            TerrainType::Plains => 2,
+            TerrainType::Desert | TerrainType::Forest => 3,
+            TerrainType::Snow | TerrainType::Jungle => 4,
+            TerrainType::Swamp => 5,
+            // Impassable; tiles are blocked anyway
+            TerrainType::Water | TerrainType::Mountains => 10,
+        }
+    }
+
+    // Whether this terrain stops line of sight `distance` tiles from a viewer
+    // whose eyes are at `eye_height`.
+    pub fn blocks_sight(&self, tile_height: f32, distance: i32, eye_height: f32) -> bool {
+        match self {
+            // Peaks block the view unless we are looking down on them
+            TerrainType::Mountains => tile_height >= eye_height,
+            // You can see a little way into the trees, and over them from high ground
+            TerrainType::Forest | TerrainType::Jungle => {
+                distance > FOREST_SIGHT && tile_height + CANOPY_HEIGHT >= eye_height
+            }
+            _ => false,
+        }
+    }
 }
 
 
 impl Tile {
-    
+    pub fn elevation_meters(&self) -> i32 {
+        (((self.height - SEA_LEVEL) * METERS_PER_HEIGHT).round() as i32).max(0)
+    }
+
+    // How far you can see from this tile: higher ground reveals more
+    pub fn sight_radius(&self) -> i32 {
+        let climb = ((self.height - LOWLAND_HEIGHT) / HEIGHT_PER_SIGHT_STEP).floor() as i32;
+        FOG_RADIUS + climb.clamp(0, MAX_HEIGHT_BONUS)
+    }
+
+    // The settlement on this tile as far as the player can tell; hidden
+    // places look like open country until they reveal themselves
+    pub fn known_location(&self) -> Option<&Location> {
+        self.location.as_ref().filter(|location| !location.concealed())
+    }
 }
 
 pub type TileGrid = Vec<Vec<Tile>>;
 const FOG_RADIUS: i32 = 4;
+const FOREST_SIGHT: i32 = 2;
+
+// Elevation and vision tuning; heights are the generator's 0.0..1.0 noise values
+const SEA_LEVEL: f32 = 0.5;
+const METERS_PER_HEIGHT: f32 = 8000.0;
+const LOWLAND_HEIGHT: f32 = 0.6;
+const HEIGHT_PER_SIGHT_STEP: f32 = 0.03;
+const MAX_HEIGHT_BONUS: i32 = 6;
+const EYE_HEIGHT: f32 = 0.01;
+const CANOPY_HEIGHT: f32 = 0.03;
+const SUMMIT_HEIGHT: f32 = 0.65;
+const SURVEY_MULTIPLIER: i32 = 2;
 
 impl World {
     pub fn new(seed: u32, width:usize, height:usize) -> Self {
@@ -55,6 +119,28 @@ impl World {
         generator.generate()
     }
 
+    // A single tile of open water, standing in for the world while no game
+    // is running, e.g. on the title screen
+    pub fn empty() -> Self {
+        let tile = Tile {
+            height: 0.0,
+            terrain: TerrainType::Water,
+            location: None,
+            blocked: true,
+            seen: false,
+        };
+        World {
+            seed: 0,
+            generation_seed: 0,
+            width: 1,
+            height: 1,
+            wraparound: true,
+            tiles: vec![vec![tile]],
+            visible: HashSet::new(),
+            surveyed_from: None,
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -81,43 +167,174 @@ impl World {
         }
     }
 
-    pub fn update(&mut self, player_pos: &Position) {
-        self.update_visibility(player_pos, FOG_RADIUS);
+    // `sight_bonus` widens (or narrows) the view, e.g. for keen eyes or darkness
+    pub fn update(&mut self, player_pos: &Position, sight_bonus: i32) {
+        if self.surveyed_from == Some(*player_pos) {
+            return;
+        }
+        self.surveyed_from = None;
+        let radius = (self.get_tile(player_pos).sight_radius() + sight_bonus).max(1);
+        self.update_visibility(player_pos, radius);
+    }
+
+    // Climb to the top of a summit and take in the view. Returns false when
+    // the player is not standing on one.
+    pub fn survey(&mut self, player_pos: &Position, sight_bonus: i32) -> bool {
+        if !self.is_summit(player_pos) {
+            return false;
+        }
+        let radius = ((self.get_tile(player_pos).sight_radius() + sight_bonus) * SURVEY_MULTIPLIER).max(1);
+        self.update_visibility(player_pos, radius);
+        self.surveyed_from = Some(*player_pos);
+        true
+    }
+
+    // A passable tile on high ground with nothing higher right next to it
+    pub fn is_summit(&self, position: &Position) -> bool {
+        let tile = self.get_tile(position);
+        if tile.blocked || tile.height < SUMMIT_HEIGHT {
+            return false;
+        }
+        let (px, py) = (position.x as i32, position.y as i32);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if let Some(pos) = self.get_valid_position(px + dx, py + dy)
+                    && self.get_tile(&pos).height > tile.height
+                {
+                    return false;
+                }
+            }
+        }
+        true
     }
 
     fn update_visibility(&mut self, position: &Position, radius: i32) {
         let (px, py) = (position.x as i32, position.y as i32);
-        
-        for dy in -radius..=radius {
-            for dx in -radius..=radius {
-                // Only update tiles within the circular radius
-                if dx*dx + dy*dy <= radius*radius {
-                    let world_x = px + dx;
-                    let world_y = py + dy;
-                    
-                    let current_pos = Position {
-                        x: world_x as usize,
-                        y: world_y as usize,
-                    };
-                    
-                    // Get the wrapped position and mark it as seen
-                    if let Some(wrapped_pos) = self.get_valid_position(&current_pos) {
-                        self.tiles[wrapped_pos.y][wrapped_pos.x].seen = true;
-                    }
+        let eye_height = self.get_tile(position).height + EYE_HEIGHT;
+        let mut candidates = HashSet::new();
+
+        // Offsets are relative to the player; wrap them onto the world grid
+        let wrap = |world: &World, dx: i32, dy: i32| {
+            world.get_valid_position(px + dx, py + dy)
+        };
+
+        // Shadowcasting handles terrain that blocks outright...
+        compute_fov(
+            radius,
+            &mut |dx, dy| match wrap(self, dx, dy) {
+                Some(pos) => {
+                    let tile = self.get_tile(&pos);
+                    let distance = dx.abs().max(dy.abs());
+                    tile.terrain.blocks_sight(tile.height, distance, eye_height)
+                }
+                None => true,
+            },
+            &mut |dx, dy| {
+                if wrap(self, dx, dy).is_some() {
+                    candidates.insert((dx, dy));
                 }
+            },
+        );
+
+        // ...then the lie of the land hides whatever sits behind higher ground
+        let mut visible = HashSet::new();
+        for (dx, dy) in candidates {
+            if self.has_elevation_line_of_sight(px, py, dx, dy, eye_height)
+                && let Some(pos) = wrap(self, dx, dy)
+            {
+                visible.insert(pos);
             }
         }
+
+        for pos in &visible {
+            self.tiles[pos.y][pos.x].seen = true;
+        }
+        self.visible = visible;
+    }
+
+    // Walk the line from the viewer to the target and check that no tile in
+    // between rises above the sight line.
+    fn has_elevation_line_of_sight(&self, px: i32, py: i32, dx: i32, dy: i32, eye_height: f32) -> bool {
+        let steps = dx.abs().max(dy.abs());
+        if steps <= 1 {
+            return true;
+        }
+        let Some(target_pos) = self.get_valid_position(px + dx, py + dy) else {
+            return false;
+        };
+        let target_height = self.get_tile(&target_pos).height;
+
+        for step in 1..steps {
+            let t = step as f32 / steps as f32;
+            let x = px + (dx as f32 * t).round() as i32;
+            let y = py + (dy as f32 * t).round() as i32;
+            let Some(pos) = self.get_valid_position(x, y) else {
+                return false;
+            };
+            let line_height = eye_height + (target_height - eye_height) * t;
+            if self.get_tile(&pos).height > line_height {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn is_visible(&self, position: &Position) -> bool {
+        self.visible.contains(position)
+    }
+
+    // The tile (dx, dy) away from `pos`, wrapping around the world edges
+    pub fn neighbour(&self, pos: &Position, dx: i32, dy: i32) -> Option<Position> {
+        self.get_valid_position(pos.x as i32 + dx, pos.y as i32 + dy)
+    }
+
+    // Signed offset from `from` to `to`, taking the short way round when the world wraps
+    pub fn offset(&self, from: &Position, to: &Position) -> (i32, i32) {
+        let mut dx = to.x as i32 - from.x as i32;
+        let mut dy = to.y as i32 - from.y as i32;
+        if self.wraparound {
+            let (w, h) = (self.width as i32, self.height as i32);
+            if dx.abs() > w / 2 { dx -= dx.signum() * w; }
+            if dy.abs() > h / 2 { dy -= dy.signum() * h; }
+        }
+        (dx, dy)
+    }
+
+    pub fn distance(&self, a: &Position, b: &Position) -> u32 {
+        let (dx, dy) = self.offset(a, b);
+        dx.unsigned_abs() + dy.unsigned_abs()
+    }
+
+    // Rough direction from `from` to `to` the short way round, e.g. "north-east"
+    pub fn compass(&self, from: &Position, to: &Position) -> &'static str {
+        let (dx, dy) = self.offset(from, to);
+        // Eighths of a turn, counted clockwise from east; y grows southwards
+        let octant = (dy as f32).atan2(dx as f32) / std::f32::consts::FRAC_PI_4;
+        match (octant.round() as i32).rem_euclid(8) {
+            0 => "east",
+            1 => "south-east",
+            2 => "south",
+            3 => "south-west",
+            4 => "west",
+            5 => "north-west",
+            6 => "north",
+            _ => "north-east",
+        }
+    }
+
+    pub fn min_move_cost(&self) -> u32 {
+        TerrainType::Road.move_cost()
     }
 
     // Helper function to get valid wrapped positions
-    fn get_valid_position(&self, pos: &Position) -> Option<Position> {
+    fn get_valid_position(&self, x: i32, y: i32) -> Option<Position> {
         if self.wraparound {
             Some(Position {
-                x: pos.x.rem_euclid(self.width),
-                y: pos.y.rem_euclid(self.height),
+                x: x.rem_euclid(self.width as i32) as usize,
+                y: y.rem_euclid(self.height as i32) as usize,
             })
-        } else if pos.x < self.width && pos.y < self.height {
-            Some(*pos)
+        } else if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(Position { x: x as usize, y: y as usize })
         } else {
             None
         }
@@ -128,26 +345,32 @@ impl World {
         start: &Position,
         target_species: Species,
     ) -> Option<Position> {
-        let (sx, sy) = (start.x as i32, start.y as i32);
-        let mut closest: Option<(Position, i32)> = None;
+        self.find_nearest_location(start, |_, location| location.species == target_species)
+    }
+
+    // The closest settlement, by steps along the axes, that `wanted` accepts
+    pub fn find_nearest_location(
+        &self,
+        start: &Position,
+        wanted: impl Fn(&Position, &Location) -> bool,
+    ) -> Option<Position> {
+        let mut closest: Option<(Position, u32)> = None;
 
         for (y, row) in self.tiles.iter().enumerate() {
             for (x, tile) in row.iter().enumerate() {
-                if let Some(location) = &tile.location {
-                    if location.species == target_species {
-                        let dx = sx - x as i32;
-                        let dy = sy - y as i32;
-                        let dist = dx.abs() + dy.abs();
-
-                        match closest {
-                            Some((_, best_dist)) if dist < best_dist => {
-                                closest = Some((Position { x, y }, dist));
-                            }
-                            None => {
-                                closest = Some((Position { x, y }, dist));
-                            }
-                            _ => {}
+                if let Some(location) = &tile.location
+                    && wanted(&Position { x, y }, location)
+                {
+                    let dist = self.distance(start, &Position { x, y });
+
+                    match closest {
+                        Some((_, best_dist)) if dist < best_dist => {
+                            closest = Some((Position { x, y }, dist));
+                        }
+                        None => {
+                            closest = Some((Position { x, y }, dist));
                         }
+                        _ => {}
                     }
                 }
             }
@@ -155,7 +378,168 @@ impl World {
 
         closest.map(|(pos, _)| pos)
     }
+
     pub fn get_interaction_prompt(&self, tile: &Tile) -> Option<String> {
-        tile.location.as_ref().map(|loc| loc.generate_description())
+        tile.known_location().map(|loc| loc.generate_description())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::location::{Governance, Industry, LocationState};
+
+    // A wrapping world of plains at one height
+    fn flat(width: usize, height: usize) -> World {
+        let tile = Tile {
+            height: SEA_LEVEL + 0.05,
+            terrain: TerrainType::Plains,
+            location: None,
+            blocked: false,
+            seen: false,
+        };
+        World {
+            seed: 1,
+            generation_seed: 1,
+            width,
+            height,
+            wraparound: true,
+            tiles: vec![vec![tile; width]; height],
+            visible: HashSet::new(),
+            surveyed_from: None,
+        }
+    }
+
+    fn town(species: Species) -> Location {
+        Location {
+            name: "Testford".to_string(),
+            species,
+            governance: Governance::Council,
+            state: LocationState::Thriving,
+            size: 40,
+            industry: Industry::Farming,
+            searched: false,
+            prosperity: 0,
+            revealed: true,
+        }
+    }
+
+    fn set(world: &mut World, x: usize, y: usize, terrain: TerrainType, height: f32) {
+        let tile = &mut world.tiles[y][x];
+        tile.terrain = terrain;
+        tile.height = height;
+        tile.blocked = matches!(terrain, TerrainType::Water | TerrainType::Mountains);
+    }
+
+    #[test]
+    fn sight_wraps_around_the_edges() {
+        let mut world = flat(20, 20);
+        world.update(&Position::new(0, 0), 0);
+        assert!(world.is_visible(&Position::new(19, 0)));
+        assert!(world.is_visible(&Position::new(0, 17)));
+        assert!(world.tiles[19][18].seen);
+        assert!(!world.is_visible(&Position::new(10, 10)));
+
+        // Without wrapping the edge is the end of the world
+        world.wraparound = false;
+        world.update(&Position::new(0, 0), 0);
+        assert!(!world.is_visible(&Position::new(19, 0)));
+        assert!(world.is_visible(&Position::new(2, 0)));
+    }
+
+    #[test]
+    fn mountains_and_deep_forest_block_the_view() {
+        let mut world = flat(20, 20);
+        let low = SEA_LEVEL + 0.05;
+        set(&mut world, 12, 10, TerrainType::Mountains, 0.9);
+        set(&mut world, 10, 12, TerrainType::Forest, low);
+        set(&mut world, 10, 13, TerrainType::Forest, low);
+        set(&mut world, 10, 14, TerrainType::Forest, low);
+        world.update(&Position::new(10, 10), 0);
+        assert!(world.is_visible(&Position::new(12, 10)));
+        assert!(!world.is_visible(&Position::new(13, 10)));
+        // A couple of trees deep is as far as you can see into a wood
+        assert!(world.is_visible(&Position::new(10, 12)));
+        assert!(world.is_visible(&Position::new(10, 13)));
+        assert!(!world.is_visible(&Position::new(10, 14)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn higher_ground_sees_further() {
+        let mut world = flat(40, 40);
+        let low = world.tiles[0][0].clone();
+        assert_eq!(low.sight_radius(), FOG_RADIUS);
+        set(&mut world, 20, 20, TerrainType::Plains, LOWLAND_HEIGHT + HEIGHT_PER_SIGHT_STEP * 3.5);
+        assert_eq!(world.tiles[20][20].sight_radius(), FOG_RADIUS + 3);
+        world.update(&Position::new(20, 20), 0);
+        assert!(world.is_visible(&Position::new(20 + FOG_RADIUS as usize + 2, 20)));
+        // but no hill shows the whole world
+        set(&mut world, 20, 20, TerrainType::Plains, 1.0);
+        assert_eq!(world.tiles[20][20].sight_radius(), FOG_RADIUS + MAX_HEIGHT_BONUS);
+    }
+
+    #[test]
+    fn a_ridge_hides_the_valley_behind_it() {
+        let mut world = flat(20, 20);
+        let low = SEA_LEVEL + 0.05;
+        // A hill the viewer can see over, then a ridge higher than their eyes
+        set(&mut world, 12, 10, TerrainType::Plains, low + 0.2);
+        world.update(&Position::new(10, 10), 0);
+        assert!(world.is_visible(&Position::new(12, 10)));
+        assert!(!world.is_visible(&Position::new(13, 10)));
+        assert!(!world.is_visible(&Position::new(14, 10)));
+        // The rest of the plain is in plain sight
+        assert!(world.is_visible(&Position::new(8, 10)));
+        assert!(world.is_visible(&Position::new(10, 13)));
+
+        // From on top of the ridge the valley is laid out below
+        world.update(&Position::new(12, 10), 0);
+        assert!(world.is_visible(&Position::new(14, 10)));
+        assert!(world.is_visible(&Position::new(10, 10)));
+    }
+
+    #[test]
+    fn surveying_from_a_summit() {
+        let mut world = flat(60, 60);
+        let peak = Position::new(30, 30);
+        set(&mut world, 30, 30, TerrainType::Plains, SUMMIT_HEIGHT + 0.05);
+        set(&mut world, 31, 30, TerrainType::Plains, SUMMIT_HEIGHT);
+        assert!(world.is_summit(&peak));
+        assert!(!world.is_summit(&Position::new(31, 30)));
+        assert!(!world.is_summit(&Position::new(10, 10)));
+        assert!(!world.survey(&Position::new(10, 10), 0));
+
+        let radius = world.tiles[30][30].sight_radius();
+        let far = Position::new(30 + radius as usize + 2, 30);
+        world.update(&peak, 0);
+        assert!(!world.is_visible(&far));
+        assert!(world.survey(&peak, 0));
+        assert!(world.is_visible(&far));
+        // The wide view lasts while the player stays put
+        world.update(&peak, 0);
+        assert!(world.is_visible(&far));
+        world.update(&Position::new(31, 30), 0);
+        assert!(!world.is_visible(&far));
+
+        // Mountains are climbed over, not stood on
+        set(&mut world, 30, 30, TerrainType::Mountains, SUMMIT_HEIGHT + 0.05);
+        assert!(!world.is_summit(&peak));
+    }
+
+    #[test]
+    fn the_nearest_town_may_be_across_the_seam() {
+        let mut world = flat(32, 32);
+        let here = Position::new(1, 5);
+        let (over_the_seam, inland) = (Position::new(30, 4), Position::new(8, 5));
+        world.tiles[over_the_seam.y][over_the_seam.x].location = Some(town(Species::Elf));
+        world.tiles[inland.y][inland.x].location = Some(town(Species::Human));
+
+        assert_eq!(world.find_nearest_location(&here, |_, _| true), Some(over_the_seam));
+        assert_eq!(world.compass(&here, &over_the_seam), "west");
+        assert_eq!(world.compass(&here, &inland), "east");
+        assert_eq!(world.compass(&here, &Position::new(2, 31)), "north");
+
+        world.wraparound = false;
+        assert_eq!(world.find_nearest_location(&here, |_, _| true), Some(inland));
+        assert_eq!(world.compass(&here, &over_the_seam), "east");
+    }
+}